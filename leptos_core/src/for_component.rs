@@ -1,11 +1,11 @@
-use leptos_dom::Element;
+use leptos_dom::{Child, Element, Node};
 use leptos_macro::*;
-use leptos_reactive::{create_effect, Memo, ReadSignal, Scope};
+use leptos_reactive::{create_effect, Memo, ReadSignal, Scope, SignalVec};
 use std::fmt::Debug;
 use std::hash::Hash;
 
 use crate as leptos;
-use crate::map::map_keyed;
+use crate::map::{map_keyed, map_signal_vec};
 
 /// Properties for the [For](crate::For) component.
 #[derive(Props)]
@@ -40,3 +40,32 @@ where
     let map_fn = (props.children)().swap_remove(0);
     map_keyed(cx, props.each, map_fn, props.key)
 }
+
+/// Properties for the [ForVec](crate::ForVec) component.
+#[derive(Props)]
+pub struct ForVecProps<T, G>
+where
+    G: Fn(Scope, &T) -> Element,
+    T: Clone + 'static,
+{
+    pub each: SignalVec<T>,
+    pub children: Box<dyn Fn() -> Vec<G>>,
+}
+
+/// Iterates over the items in a [SignalVec] and displays them, like [For].
+///
+/// Rather than comparing the whole list each time it changes, this applies each change
+/// made to the [SignalVec] (pushing, inserting, removing, moving or setting an item)
+/// directly, both to the mapped rows and to the rendered DOM nodes, so changing a single
+/// row of a large table only renders and inserts that row.
+#[allow(non_snake_case)]
+pub fn ForVec<T, G>(cx: Scope, props: ForVecProps<T, G>) -> Child
+where
+    G: Fn(Scope, &T) -> Element + 'static,
+    T: Clone + 'static,
+{
+    let map_fn = (props.children)().swap_remove(0);
+    Child::List(map_signal_vec(cx, props.each, move |cx, item| {
+        Node::from(map_fn(cx, item))
+    }))
+}
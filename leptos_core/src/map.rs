@@ -1,6 +1,6 @@
 use leptos_reactive::{
     create_effect, create_memo, create_signal, queue_microtask, Memo, ReadSignal, Scope,
-    ScopeDisposer,
+    ScopeDisposer, SignalVec,
 };
 use std::{collections::HashMap, fmt::Debug, hash::Hash, ops::IndexMut};
 
@@ -156,6 +156,24 @@ where
     })
 }
 
+/// Maps a [SignalVec] to another [SignalVec] via a map function, like [map_keyed]. Rather
+/// than diffing the whole input `Vec` each time it changes, this applies each change made to
+/// the [SignalVec] directly, so updating, inserting, or removing a single item only maps that
+/// item, and is passed on to whatever renders the mapped list as a change of its own.
+///
+/// This function is the underlying utility behind `ForVec`. See [SignalVec::map].
+pub fn map_signal_vec<T, U>(
+    cx: Scope,
+    list: SignalVec<T>,
+    map_fn: impl Fn(Scope, &T) -> U + 'static,
+) -> SignalVec<U>
+where
+    T: Clone + 'static,
+    U: Clone + 'static,
+{
+    list.map(cx, map_fn)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::map::map_keyed;
@@ -56,7 +56,7 @@ where
 {
     move || {
         #[cfg(feature = "transition")]
-        let transition_pending = cx.transition_pending();
+        let transition_pending = context.transition_pending();
 
         #[cfg(not(feature = "transition"))]
         let transition_pending = false;
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use leptos_reactive::Scope;
+use leptos_reactive::{Scope, SignalVec};
 
 #[cfg(any(feature = "csr", feature = "hydrate"))]
 use wasm_bindgen::JsCast;
@@ -14,6 +14,9 @@ pub enum Child {
     Fn(Rc<RefCell<dyn FnMut() -> Child>>),
     Node(Node),
     Nodes(Vec<Node>),
+    /// A list of nodes that is updated one change at a time, like the one rendered by `ForVec`,
+    /// rather than by comparing it to its previous value.
+    List(SignalVec<Node>),
 }
 
 impl Child {
@@ -31,6 +34,7 @@ impl Child {
             }
             Child::Node(node) => node.to_string(),
             Child::Nodes(nodes) => nodes.iter().cloned().collect(),
+            Child::List(list) => list.with(|nodes| nodes.iter().cloned().collect()),
         }
     }
 }
@@ -43,6 +47,7 @@ impl std::fmt::Debug for Child {
             Self::Fn(_) => f.debug_tuple("Fn").finish(),
             Self::Node(arg0) => f.debug_tuple("Node").field(arg0).finish(),
             Self::Nodes(arg0) => f.debug_tuple("Nodes").field(arg0).finish(),
+            Self::List(arg0) => f.debug_tuple("List").field(arg0).finish(),
         }
     }
 }
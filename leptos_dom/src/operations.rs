@@ -3,6 +3,7 @@ use std::time::Duration;
 use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
 
 use crate::{debug_warn, event_delegation, is_server};
+use leptos_reactive::Scope;
 
 thread_local! {
     pub static WINDOW: web_sys::Window = web_sys::window().unwrap_throw();
@@ -227,23 +228,57 @@ pub fn set_interval(
     Ok(IntervalHandle(handle))
 }
 
+/// Adds a delegated event listener to the element. The handler runs in a [Scope::batch],
+/// so any signals it updates only notify their effects once it has finished.
 pub fn add_event_listener(
+    cx: Scope,
     target: &web_sys::Element,
     event_name: &'static str,
-    cb: impl FnMut(web_sys::Event) + 'static,
+    mut cb: impl FnMut(web_sys::Event) + 'static,
 ) {
+    let cb = move |ev| cx.batch(|| cb(ev));
     let cb = Closure::wrap(Box::new(cb) as Box<dyn FnMut(web_sys::Event)>).into_js_value();
     let key = event_delegation::event_delegation_key(event_name);
     _ = js_sys::Reflect::set(target, &JsValue::from_str(&key), &cb);
     event_delegation::add_event_listener(event_name);
 }
 
-pub fn window_event_listener(event_name: &str, cb: impl Fn(web_sys::Event) + 'static) {
+/// Adds an event listener to the `window`, returning a handle that can be used to remove it,
+/// for example in [on_cleanup](leptos_reactive::on_cleanup).
+pub fn window_event_listener(
+    event_name: &str,
+    cb: impl Fn(web_sys::Event) + 'static,
+) -> WindowListenerHandle {
     if !is_server!() {
         let handler = Box::new(cb) as Box<dyn FnMut(web_sys::Event)>;
 
         let cb = Closure::wrap(handler).into_js_value();
         _ = window().add_event_listener_with_callback(event_name, cb.unchecked_ref());
+
+        let event_name = event_name.to_string();
+        WindowListenerHandle(Some(Box::new(move || {
+            _ = window().remove_event_listener_with_callback(&event_name, cb.unchecked_ref());
+        })))
+    } else {
+        WindowListenerHandle(None)
+    }
+}
+
+/// A handle to an event listener added with [window_event_listener].
+pub struct WindowListenerHandle(Option<Box<dyn FnOnce()>>);
+
+impl WindowListenerHandle {
+    /// Removes the event listener from the `window`.
+    pub fn remove(self) {
+        if let Some(remove) = self.0 {
+            remove();
+        }
+    }
+}
+
+impl std::fmt::Debug for WindowListenerHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WindowListenerHandle").finish()
     }
 }
 
@@ -1,4 +1,4 @@
-use leptos_reactive::{create_render_effect, Scope};
+use leptos_reactive::{create_render_effect, Scope, SignalVec, VecDiff};
 use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
 
 use crate::{
@@ -171,6 +171,7 @@ pub fn insert(
                 }
             });
         }
+        Child::List(list) => insert_list(cx, parent.unchecked_into(), list, before, initial),
         _ => {
             insert_expression(
                 cx,
@@ -183,6 +184,64 @@ pub fn insert(
     }
 }
 
+/// Inserts a [SignalVec] of nodes, then applies each change made to it to the DOM directly,
+/// so that pushing, removing or setting one node only touches that node.
+fn insert_list(
+    cx: Scope,
+    parent: web_sys::Element,
+    list: SignalVec<web_sys::Node>,
+    before: Marker,
+    initial: Option<Child>,
+) {
+    if let Some(initial) = initial.filter(|initial| initial != &Child::Null) {
+        insert_expression(cx, parent.clone(), &Child::Null, initial, &before);
+    }
+
+    let diffs = list.subscribe(cx);
+    let mut nodes: Vec<web_sys::Node> = Vec::new();
+    create_render_effect(cx, move |_| {
+        for diff in diffs.take() {
+            match diff {
+                VecDiff::Replace(new_nodes) => {
+                    for node in nodes.drain(..) {
+                        remove_child(&parent, &node);
+                    }
+                    nodes = append_nodes(parent.clone(), new_nodes, before.as_some_node().cloned());
+                }
+                VecDiff::Insert { index, value } => {
+                    let next = nodes.get(index).or_else(|| before.as_some_node());
+                    let node = insert_before(&parent, &value, next);
+                    nodes.insert(index, node);
+                }
+                VecDiff::Set { index, value } => {
+                    replace_child(&parent, &value, &nodes[index]);
+                    nodes[index] = value;
+                }
+                VecDiff::RemoveAt { index } => remove_child(&parent, &nodes.remove(index)),
+                VecDiff::Move { from, to } => {
+                    let node = nodes.remove(from);
+                    let next = nodes.get(to).or_else(|| before.as_some_node());
+                    insert_before(&parent, &node, next);
+                    nodes.insert(to, node);
+                }
+                VecDiff::Push(value) => {
+                    nodes.push(insert_before(&parent, &value, before.as_some_node()));
+                }
+                VecDiff::Pop => {
+                    if let Some(node) = nodes.pop() {
+                        remove_child(&parent, &node);
+                    }
+                }
+                VecDiff::Clear => {
+                    for node in nodes.drain(..) {
+                        remove_child(&parent, &node);
+                    }
+                }
+            }
+        }
+    });
+}
+
 pub fn insert_expression(
     cx: Scope,
     parent: web_sys::Element,
@@ -253,7 +312,7 @@ pub fn insert_expression(
                     replace_with(old_node.unchecked_ref(), node);
                     Child::Node(node.clone())
                 }
-                Child::Fn(_) => {
+                Child::Fn(_) | Child::List(_) => {
                     debug_warn!(
                         "{}: replacing a Child::Node<{}> with Child::Fn<...>",
                         std::panic::Location::caller(),
@@ -289,6 +348,10 @@ pub fn insert_expression(
                 }
                 value
             }
+            // a list returned by a function is rendered again whenever it changes
+            Child::List(list) => {
+                insert_expression(cx, parent, &Child::Nodes(list.get()), current, before)
+            }
         }
     }
 }
@@ -428,7 +491,7 @@ fn clean_children(
                     Child::Node(node)
                 }
             }
-            Child::Fn(_) => todo!(),
+            Child::Fn(_) | Child::List(_) => todo!(),
         }
     }
 }
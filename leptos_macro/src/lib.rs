@@ -29,6 +29,7 @@ mod view;
 use view::render_view;
 mod component;
 mod props;
+mod store;
 
 /// The `view` macro uses RSX (like JSX, but Rust!) It follows most of the
 /// same rules as HTML, with the following differences:
@@ -195,6 +196,41 @@ pub fn derive_prop(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Generates fine-grained accessors for the fields of a struct held in a
+/// [Store](https://docs.rs/leptos_reactive/latest/leptos_reactive/struct.Store.html), as a
+/// trait named `{StructName}StoreFields`. Each accessor returns a field that can be read
+/// and written on its own, so `store.user().name().set(..)` only notifies the readers of
+/// `name` (and of `user` and the whole store, which contain it).
+///
+/// A `Vec` field marked `#[store(key: KeyType = |item| item.key)]` tracks each of its
+/// items by key, and can be iterated with `.items()` or indexed with `.at_key(key)`.
+/// ```rust,ignore
+/// #[derive(Store)]
+/// struct Todos {
+///     filter: String,
+///     #[store(key: usize = |todo| todo.id)]
+///     todos: Vec<Todo>,
+/// }
+///
+/// #[derive(Store)]
+/// struct Todo {
+///     id: usize,
+///     title: String,
+///     completed: bool,
+/// }
+///
+/// let store = create_store(cx, Todos { filter: String::new(), todos: vec![] });
+/// store.todos().at_key(0).completed().set(true);
+/// ```
+#[proc_macro_derive(Store, attributes(store))]
+pub fn derive_store(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    store::impl_store(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 // Derive Params trait for routing
 #[proc_macro_derive(Params, attributes(params))]
 pub fn params_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
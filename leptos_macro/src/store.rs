@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::ParseStream, spanned::Spanned, Data, DataStruct, DeriveInput, Expr, Fields,
+    GenericArgument, PathArguments, Token, Type,
+};
+
+pub fn impl_store(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let trait_name = format_ident!("{}StoreFields", name);
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let fields = match &ast.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new(
+                ast.span(),
+                "#[derive(Store)] can only be used on structs with named fields",
+            ))
+        }
+    };
+
+    let accessors = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let ident = field.ident.as_ref().unwrap();
+            let ty = &field.ty;
+            let subfield = quote! {
+                leptos::Subfield::new(self.clone(), #index, |v| &v.#ident, |v| &mut v.#ident)
+            };
+
+            match keyed_attr(field)? {
+                None => Ok(quote! {
+                    fn #ident(&self) -> leptos::Subfield<Self, #ty> {
+                        #subfield
+                    }
+                }),
+                Some((key_ty, key_fn)) => {
+                    let item_ty = vec_item_type(ty).ok_or_else(|| {
+                        syn::Error::new(ty.span(), "#[store(key)] can only be used on a Vec field")
+                    })?;
+                    Ok(quote! {
+                        fn #ident(&self) -> leptos::Keyed<leptos::Subfield<Self, #ty>, #item_ty, #key_ty> {
+                            leptos::Keyed::new(#subfield, #key_fn)
+                        }
+                    })
+                }
+            }
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let doc = format!(
+        "Accessors for the fields of a [{}] in a [Store](leptos::Store), generated by `#[derive(Store)]`.",
+        name
+    );
+
+    // the trait has the struct's generics, plus one for the field it's implemented on
+    let mut field_generics = ast.generics.clone();
+    field_generics
+        .params
+        .push(syn::parse_quote!(__StoreField: leptos::StoreField<Value = #name #ty_generics>));
+    let (field_impl_generics, _, _) = field_generics.split_for_impl();
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis trait #trait_name #impl_generics: leptos::StoreField<Value = #name #ty_generics> #where_clause {
+            #(#accessors)*
+        }
+
+        impl #field_impl_generics #trait_name #ty_generics for __StoreField #where_clause {}
+    })
+}
+
+/// Parses `#[store(key: KeyType = |item| item.key)]`.
+fn keyed_attr(field: &syn::Field) -> syn::Result<Option<(Type, Expr)>> {
+    let attr = match field.attrs.iter().find(|attr| attr.path.is_ident("store")) {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+    attr.parse_args_with(|input: ParseStream| {
+        let kw: syn::Ident = input.parse()?;
+        if kw != "key" {
+            return Err(syn::Error::new(kw.span(), "expected `key`"));
+        }
+        input.parse::<Token![:]>()?;
+        let key_ty: Type = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let key_fn: Expr = input.parse()?;
+        Ok(Some((key_ty, key_fn)))
+    })
+}
+
+/// Returns `T` for a field of type `Vec<T>`.
+fn vec_item_type(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Vec" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(item_ty) => Some(item_ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
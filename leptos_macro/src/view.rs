@@ -464,7 +464,7 @@ fn attr_to_tokens(
                 .as_ref()
                 .expect("event listener attributes need a value");
             expressions.push(quote_spanned! {
-                span => add_event_listener(#el_id.unchecked_ref(), #event_name, #handler);
+                span => add_event_listener(#cx, #el_id.unchecked_ref(), #event_name, #handler);
             });
         }
     }
@@ -890,7 +890,7 @@ fn create_component(cx: &Ident, node: &Node, mode: Mode) -> TokenStream {
                 .as_ref()
                 .expect("event listener attributes need a value");
             Some(quote_spanned! {
-                span => add_event_listener(#component_name.unchecked_ref(), #event_name, #handler)
+                span => add_event_listener(#cx, #component_name.unchecked_ref(), #event_name, #handler)
             })
         }
         // Properties
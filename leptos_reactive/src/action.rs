@@ -0,0 +1,192 @@
+use std::{fmt::Debug, future::Future};
+
+use crate::{
+    create_signal,
+    spawn::spawn_task,
+    sync::{Cell, Shared},
+    MaybeSend, PinnedFuture, ReadSignal, Scope, ThreadSafe, WriteSignal,
+};
+
+/// Creates an [Action], which runs an `async` function in response to user input,
+/// like submitting a form, and tracks its state with signals.
+///
+/// Where a [Resource](crate::Resource) loads data whenever its source changes, an action
+/// only runs when you [dispatch](Action::dispatch) it. Its [version](Action::version)
+/// increases every time it finishes, so a resource that uses it as part of its source
+/// will refetch after each mutation.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// async fn add_todo(title: String) -> usize {
+///   // pretend we're saving it somewhere
+///   title.len()
+/// }
+///
+/// let save = create_action(cx, |title: &String| add_todo(title.clone()));
+///
+/// // a resource that refetches whenever the action has run
+/// let todos = create_resource(cx, save.version(), |version| async move { version });
+/// assert_eq!(todos(), Some(0));
+///
+/// save.dispatch("Buy milk".to_string());
+///
+/// // the action has finished, so it is no longer pending and holds its value
+/// assert!(!save.pending()());
+/// assert_eq!(save.value()(), Some(8));
+/// assert_eq!(todos(), Some(1));
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_action<I, O, Fu>(
+    cx: Scope,
+    action_fn: impl Fn(&I) -> Fu + ThreadSafe + 'static,
+) -> Action<I, O>
+where
+    I: Clone + ThreadSafe + 'static,
+    O: Clone + ThreadSafe + 'static,
+    Fu: Future<Output = O> + MaybeSend + 'static,
+{
+    let (version, set_version) = create_signal(cx, 0);
+    let (input, set_input) = create_signal(cx, None);
+    let (value, set_value) = create_signal(cx, None);
+    let (pending, set_pending) = create_signal(cx, false);
+    let action_fn: ActionFn<I, O> =
+        Shared::new(move |input: &I| Box::pin(action_fn(input)) as PinnedFuture<O>);
+
+    Action {
+        version,
+        set_version,
+        input,
+        set_input,
+        value,
+        set_value,
+        pending,
+        set_pending,
+        pending_dispatches: Default::default(),
+        action_fn,
+    }
+}
+
+/// An action that runs an `async` function when it’s dispatched. See [create_action].
+pub struct Action<I, O>
+where
+    I: 'static,
+    O: 'static,
+{
+    version: ReadSignal<usize>,
+    set_version: WriteSignal<usize>,
+    input: ReadSignal<Option<I>>,
+    set_input: WriteSignal<Option<I>>,
+    value: ReadSignal<Option<O>>,
+    set_value: WriteSignal<Option<O>>,
+    pending: ReadSignal<bool>,
+    set_pending: WriteSignal<bool>,
+    /// The number of dispatches that haven’t finished yet.
+    pending_dispatches: Shared<Cell<usize>>,
+    action_fn: ActionFn<I, O>,
+}
+
+impl<I, O> Action<I, O>
+where
+    I: Clone + ThreadSafe + 'static,
+    O: Clone + ThreadSafe + 'static,
+{
+    /// Runs the action with the given input. While it’s running, [pending](Action::pending)
+    /// is `true` and [input](Action::input) holds the input; when it finishes,
+    /// [value](Action::value) holds its result and [version](Action::version) increases.
+    pub fn dispatch(&self, input: I) {
+        let fut = (self.action_fn)(&input);
+
+        self.pending_dispatches
+            .set(self.pending_dispatches.get() + 1);
+        self.set_input.update(|n| *n = Some(input));
+        self.set_pending.update(|n| *n = true);
+
+        spawn_task(self.set_version.runtime, {
+            let set_version = self.set_version;
+            let set_input = self.set_input;
+            let set_value = self.set_value;
+            let set_pending = self.set_pending;
+            let pending_dispatches = Shared::clone(&self.pending_dispatches);
+            async move {
+                let res = fut.await;
+
+                // the action may have been disposed while it was running
+                _ = set_value.try_update(|n| *n = Some(res));
+                _ = set_version.try_update(|n| *n += 1);
+
+                // if it was dispatched again in the meantime, it’s still pending
+                let remaining = pending_dispatches.get().saturating_sub(1);
+                pending_dispatches.set(remaining);
+                if remaining == 0 {
+                    _ = set_input.try_update(|n| *n = None);
+                    _ = set_pending.try_update(|n| *n = false);
+                }
+            }
+        })
+    }
+
+    /// The number of times the action has finished running. Use this as part of a
+    /// [Resource](crate::Resource)’s source to refetch it after the action runs.
+    pub fn version(&self) -> ReadSignal<usize> {
+        self.version
+    }
+
+    /// The input the action was most recently dispatched with, while it is running.
+    pub fn input(&self) -> ReadSignal<Option<I>> {
+        self.input
+    }
+
+    /// The value returned by the action the last time it finished.
+    pub fn value(&self) -> ReadSignal<Option<O>> {
+        self.value
+    }
+
+    /// Whether the action is currently running.
+    pub fn pending(&self) -> ReadSignal<bool> {
+        self.pending
+    }
+}
+
+impl<I, O> Clone for Action<I, O>
+where
+    I: Clone + 'static,
+    O: Clone + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            version: self.version,
+            set_version: self.set_version,
+            input: self.input,
+            set_input: self.set_input,
+            value: self.value,
+            set_value: self.set_value,
+            pending: self.pending,
+            set_pending: self.set_pending,
+            pending_dispatches: Shared::clone(&self.pending_dispatches),
+            action_fn: Shared::clone(&self.action_fn),
+        }
+    }
+}
+
+impl<I, O> Debug for Action<I, O>
+where
+    I: Debug + 'static,
+    O: Debug + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Action")
+            .field("version", &self.version)
+            .field("input", &self.input)
+            .field("value", &self.value)
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(not(feature = "multithreaded"))]
+type ActionFn<I, O> = Shared<dyn Fn(&I) -> PinnedFuture<O>>;
+
+#[cfg(feature = "multithreaded")]
+type ActionFn<I, O> = Shared<dyn Fn(&I) -> PinnedFuture<O> + Send + Sync>;
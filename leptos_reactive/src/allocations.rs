@@ -0,0 +1,208 @@
+#[cfg(feature = "resource")]
+use crate::ResourceId;
+use crate::{
+    graph::GraphNode, with_runtime, EffectId, Runtime, Scope, ScopeId, ScopeProperty, SignalId,
+};
+use std::{fmt::Display, panic::Location};
+
+/// Everything that is still alive in a reactive runtime, grouped by the scope that owns
+/// it, along with where each of them was created. Take one with [Scope::allocations].
+///
+/// This is useful for tracking down leaks: a scope that is never disposed (for example,
+/// because the [ScopeDisposer](crate::ScopeDisposer) returned by [create_scope](crate::create_scope)
+/// was dropped) keeps its signals, effects, and resources alive for as long as the runtime,
+/// and its `Display` output points to the line that created it.
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// let before = cx.allocations().counts();
+///
+/// let disposer = cx.child_scope(|cx| {
+///   let (count, _) = create_signal(cx, 0);
+///   create_isomorphic_effect(cx, move |_| count());
+/// });
+/// assert_eq!(cx.allocations().counts().signals, before.signals + 1);
+/// println!("{}", cx.allocations());
+///
+/// // once the child scope is disposed, we're back to where we started
+/// disposer.dispose();
+/// cx.assert_allocations(before);
+/// # }).dispose();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AllocationReport {
+    pub scopes: Vec<ScopeAllocations>,
+}
+
+/// A scope in an [AllocationReport], and everything it owns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopeAllocations {
+    pub id: ScopeId,
+    pub parent: Option<ScopeId>,
+    pub created_at: Option<&'static Location<'static>>,
+    /// The signals it owns, including those holding the values of memos.
+    pub signals: Vec<Allocation<SignalId>>,
+    /// The effects it owns, including the computations behind memos.
+    pub effects: Vec<Allocation<EffectId>>,
+    #[cfg(feature = "resource")]
+    pub resources: Vec<Allocation<ResourceId>>,
+}
+
+/// A signal, effect, or resource in an [AllocationReport].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Allocation<T> {
+    pub id: T,
+    pub created_at: Option<&'static Location<'static>>,
+}
+
+/// The number of each kind of thing in an [AllocationReport].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AllocationCounts {
+    pub scopes: usize,
+    pub signals: usize,
+    pub effects: usize,
+    #[cfg(feature = "resource")]
+    pub resources: usize,
+}
+
+impl AllocationReport {
+    pub fn counts(&self) -> AllocationCounts {
+        self.scopes
+            .iter()
+            .fold(AllocationCounts::default(), |counts, scope| {
+                AllocationCounts {
+                    scopes: counts.scopes + 1,
+                    signals: counts.signals + scope.signals.len(),
+                    effects: counts.effects + scope.effects.len(),
+                    #[cfg(feature = "resource")]
+                    resources: counts.resources + scope.resources.len(),
+                }
+            })
+    }
+}
+
+impl Display for AllocationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts = self.counts();
+        write!(
+            f,
+            "{} scopes, {} signals, {} effects",
+            counts.scopes, counts.signals, counts.effects
+        )?;
+        #[cfg(feature = "resource")]
+        write!(f, ", {} resources", counts.resources)?;
+        writeln!(f)?;
+        for scope in &self.scopes {
+            write!(f, "scope {:?}", scope.id)?;
+            if let Some(parent) = scope.parent {
+                write!(f, " (child of {parent:?})")?;
+            }
+            writeln!(f, " created at {}", location(scope.created_at))?;
+            for signal in &scope.signals {
+                writeln!(
+                    f,
+                    "  signal {:?} created at {}",
+                    signal.id,
+                    location(signal.created_at)
+                )?;
+            }
+            for effect in &scope.effects {
+                writeln!(
+                    f,
+                    "  effect {:?} created at {}",
+                    effect.id,
+                    location(effect.created_at)
+                )?;
+            }
+            #[cfg(feature = "resource")]
+            for resource in &scope.resources {
+                writeln!(
+                    f,
+                    "  resource {:?} created at {}",
+                    resource.id,
+                    location(resource.created_at)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn location(location: Option<&'static Location<'static>>) -> String {
+    match location {
+        Some(location) => location.to_string(),
+        None => "an unknown location".to_string(),
+    }
+}
+
+impl Scope {
+    /// Lists every scope, signal, effect, and resource that is alive in the runtime this
+    /// scope belongs to. See [AllocationReport].
+    pub fn allocations(&self) -> AllocationReport {
+        with_runtime(self.runtime, |runtime| {
+            let parents = runtime.scope_parents.borrow();
+            let scopes = runtime
+                .scopes
+                .borrow()
+                .iter()
+                .map(|(id, properties)| {
+                    let mut scope = ScopeAllocations {
+                        id,
+                        parent: parents.get(id).copied(),
+                        created_at: runtime.location(id.into()),
+                        signals: Vec::new(),
+                        effects: Vec::new(),
+                        #[cfg(feature = "resource")]
+                        resources: Vec::new(),
+                    };
+                    for property in properties.borrow().iter() {
+                        match property {
+                            ScopeProperty::Signal(id) => {
+                                scope.signals.push(allocation(runtime, *id))
+                            }
+                            ScopeProperty::Effect(id) => {
+                                scope.effects.push(allocation(runtime, *id))
+                            }
+                            #[cfg(feature = "resource")]
+                            ScopeProperty::Resource(id) => {
+                                scope.resources.push(allocation(runtime, *id))
+                            }
+                            _ => {}
+                        }
+                    }
+                    scope
+                })
+                .collect();
+            AllocationReport { scopes }
+        })
+    }
+
+    /// Panics if the number of scopes, signals, effects, and resources alive in the runtime
+    /// is not `expected`, printing everything that is still alive. This is useful in tests,
+    /// to check that disposing of a scope cleans up everything it created.
+    #[track_caller]
+    pub fn assert_allocations(&self, expected: AllocationCounts) {
+        let report = self.allocations();
+        let counts = report.counts();
+        assert!(
+            counts == expected,
+            "expected {expected:?}, but found {counts:?}\n{report}"
+        );
+    }
+}
+
+fn allocation<T>(runtime: &Runtime, id: T) -> Allocation<T>
+where
+    T: Copy + Into<GraphNode>,
+{
+    Allocation {
+        id,
+        created_at: runtime.location(id.into()),
+    }
+}
+
+impl Runtime {
+    fn location(&self, node: GraphNode) -> Option<&'static Location<'static>> {
+        self.locations.borrow().get(&node).copied()
+    }
+}
@@ -0,0 +1,648 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+
+use crate::{
+    create_isomorphic_effect, create_signal, on_cleanup,
+    sync::{Cell, RefCell, Shared},
+    ReadSignal, Scope, ScopeDisposer, ThreadSafe, WriteSignal,
+};
+
+/// Creates a [SignalVec], a reactive [Vec] whose changes can be observed one at a time
+/// as [VecDiff]s, rather than by comparing the whole list to its previous value.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// let rows = create_signal_vec(cx, vec!["a", "b"]);
+///
+/// // a consumer starts with the current contents, then sees each change
+/// let diffs = rows.subscribe(cx);
+/// assert_eq!(diffs.take(), vec![VecDiff::Replace(vec!["a", "b"])]);
+///
+/// rows.push("c");
+/// rows.set(0, "A");
+/// assert_eq!(
+///   diffs.take(),
+///   vec![VecDiff::Push("c"), VecDiff::Set { index: 0, value: "A" }]
+/// );
+///
+/// // it can also be read like any other signal
+/// assert_eq!(rows(), vec!["A", "b", "c"]);
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_signal_vec<T>(cx: Scope, values: Vec<T>) -> SignalVec<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    SignalVec {
+        inner: Shared::new(SignalVecInner {
+            values: RefCell::new(values),
+            diffs: DiffQueues::new(cx),
+        }),
+    }
+}
+
+/// A change to a [SignalVec].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VecDiff<T> {
+    /// Replaces the whole list.
+    Replace(Vec<T>),
+    /// Inserts a value at `index`, shifting the values after it.
+    Insert { index: usize, value: T },
+    /// Replaces the value at `index`.
+    Set { index: usize, value: T },
+    /// Removes the value at `index`, shifting the values after it.
+    RemoveAt { index: usize },
+    /// Moves the value at `from` so that it is at `to`.
+    Move { from: usize, to: usize },
+    /// Adds a value at the end.
+    Push(T),
+    /// Removes the last value.
+    Pop,
+    /// Removes every value.
+    Clear,
+}
+
+impl<T> VecDiff<T> {
+    /// Makes the same change to another [Vec].
+    pub fn apply_to(self, values: &mut Vec<T>) {
+        match self {
+            VecDiff::Replace(new_values) => *values = new_values,
+            VecDiff::Insert { index, value } => values.insert(index, value),
+            VecDiff::Set { index, value } => values[index] = value,
+            VecDiff::RemoveAt { index } => {
+                values.remove(index);
+            }
+            VecDiff::Move { from, to } => {
+                let value = values.remove(from);
+                values.insert(to, value);
+            }
+            VecDiff::Push(value) => values.push(value),
+            VecDiff::Pop => {
+                values.pop();
+            }
+            VecDiff::Clear => values.clear(),
+        }
+    }
+}
+
+/// A reactive [Vec] whose changes are emitted as [VecDiff]s. See [create_signal_vec].
+///
+/// Reading it with [with](SignalVec::with) or [get](SignalVec::get) subscribes to any
+/// change, like a [ReadSignal]. Consumers that can update themselves from a single
+/// change, like a keyed list, should instead [subscribe](SignalVec::subscribe) to its diffs.
+pub struct SignalVec<T>
+where
+    T: 'static,
+{
+    inner: Shared<SignalVecInner<T>>,
+}
+
+struct SignalVecInner<T> {
+    values: RefCell<Vec<T>>,
+    diffs: DiffQueues<VecDiff<T>>,
+}
+
+impl<T> SignalVec<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    /// Applies a function to the current list, and subscribes the running effect to changes.
+    pub fn with<U>(&self, f: impl FnOnce(&Vec<T>) -> U) -> U {
+        self.inner.diffs.track();
+        f(&self.inner.values.borrow())
+    }
+
+    /// Clones and returns the current list, and subscribes the running effect to changes.
+    pub fn get(&self) -> Vec<T> {
+        self.with(Vec::clone)
+    }
+
+    /// The number of values in the list, which subscribes the running effect to changes.
+    pub fn len(&self) -> usize {
+        self.with(Vec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.with(Vec::is_empty)
+    }
+
+    /// Returns a [VecDiffs] that collects every change made to the list from now on,
+    /// starting with a [VecDiff::Replace] holding its current contents. It stops
+    /// collecting changes when `cx` is disposed.
+    pub fn subscribe(&self, cx: Scope) -> VecDiffs<T> {
+        let current = VecDiff::Replace(self.inner.values.borrow().clone());
+        let id = self.inner.diffs.subscribe(current);
+        on_cleanup(cx, {
+            let inner = Shared::clone(&self.inner);
+            move || inner.diffs.unsubscribe(id)
+        });
+        VecDiffs {
+            inner: Shared::clone(&self.inner),
+            id,
+        }
+    }
+
+    pub fn push(&self, value: T) {
+        self.apply(VecDiff::Push(value));
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let last = self.inner.values.borrow().last().cloned();
+        if last.is_some() {
+            self.apply(VecDiff::Pop);
+        }
+        last
+    }
+
+    /// # Panics
+    /// Panics if `index > len`.
+    pub fn insert(&self, index: usize, value: T) {
+        self.apply(VecDiff::Insert { index, value });
+    }
+
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&self, index: usize, value: T) {
+        self.apply(VecDiff::Set { index, value });
+    }
+
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&self, index: usize) -> T {
+        let value = self.inner.values.borrow()[index].clone();
+        self.apply(VecDiff::RemoveAt { index });
+        value
+    }
+
+    /// Moves the value at `from` so that it is at `to`.
+    ///
+    /// # Panics
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_item(&self, from: usize, to: usize) {
+        self.apply(VecDiff::Move { from, to });
+    }
+
+    pub fn clear(&self) {
+        self.apply(VecDiff::Clear);
+    }
+
+    /// Replaces the whole list.
+    pub fn replace(&self, values: Vec<T>) {
+        self.apply(VecDiff::Replace(values));
+    }
+
+    /// Maps each value to another one, returning a [SignalVec] that is kept up to date one
+    /// change at a time: inserting or setting a value only maps that value, and each change
+    /// is sent on to the new list's subscribers as a [VecDiff] of its own.
+    ///
+    /// Each value is mapped in its own child [Scope] of `cx`, which is disposed when the
+    /// value is removed or replaced.
+    ///
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # create_scope(|cx| {
+    /// let rows = create_signal_vec(cx, vec![1, 2]);
+    /// let labels = rows.map(cx, |_, n| format!("row {n}"));
+    /// let diffs = labels.subscribe(cx);
+    /// assert_eq!(diffs.take(), vec![VecDiff::Replace(vec!["row 1".to_string(), "row 2".to_string()])]);
+    ///
+    /// rows.set(1, 3);
+    /// assert_eq!(diffs.take(), vec![VecDiff::Set { index: 1, value: "row 3".to_string() }]);
+    /// # }).dispose();
+    /// ```
+    pub fn map<U>(
+        &self,
+        cx: Scope,
+        map_fn: impl Fn(Scope, &T) -> U + ThreadSafe + 'static,
+    ) -> SignalVec<U>
+    where
+        U: Clone + ThreadSafe + 'static,
+    {
+        let diffs = self.subscribe(cx);
+        let mapped = create_signal_vec(cx, Vec::new());
+        let mut disposers: Vec<ScopeDisposer> = Vec::new();
+
+        // values are mapped untracked, so that signals read by `map_fn` don't re-run the effect
+        let map_value = move |value: &T, disposers: &mut Vec<ScopeDisposer>, index: usize| {
+            let mut item = None;
+            let disposer = cx.untrack(|| cx.child_scope(|cx| item = Some(map_fn(cx, value))));
+            disposers.insert(index, disposer);
+            item.unwrap()
+        };
+
+        create_isomorphic_effect(cx, {
+            let mapped = mapped.clone();
+            move |_| {
+                let diffs = diffs.take();
+                cx.batch(|| {
+                    for diff in diffs {
+                        let diff = match diff {
+                            VecDiff::Replace(values) => {
+                                disposers.drain(..).for_each(ScopeDisposer::dispose);
+                                VecDiff::Replace(
+                                    values
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(index, value)| {
+                                            map_value(value, &mut disposers, index)
+                                        })
+                                        .collect(),
+                                )
+                            }
+                            VecDiff::Insert { index, value } => VecDiff::Insert {
+                                index,
+                                value: map_value(&value, &mut disposers, index),
+                            },
+                            VecDiff::Set { index, value } => {
+                                disposers.remove(index).dispose();
+                                VecDiff::Set {
+                                    index,
+                                    value: map_value(&value, &mut disposers, index),
+                                }
+                            }
+                            VecDiff::RemoveAt { index } => {
+                                disposers.remove(index).dispose();
+                                VecDiff::RemoveAt { index }
+                            }
+                            VecDiff::Move { from, to } => {
+                                let disposer = disposers.remove(from);
+                                disposers.insert(to, disposer);
+                                VecDiff::Move { from, to }
+                            }
+                            VecDiff::Push(value) => {
+                                let index = disposers.len();
+                                VecDiff::Push(map_value(&value, &mut disposers, index))
+                            }
+                            VecDiff::Pop => {
+                                if let Some(disposer) = disposers.pop() {
+                                    disposer.dispose();
+                                }
+                                VecDiff::Pop
+                            }
+                            VecDiff::Clear => {
+                                disposers.drain(..).for_each(ScopeDisposer::dispose);
+                                VecDiff::Clear
+                            }
+                        };
+                        mapped.apply(diff);
+                    }
+                });
+            }
+        });
+
+        mapped
+    }
+
+    fn apply(&self, diff: VecDiff<T>) {
+        // subscribers only see the change once it has been applied, so a change that
+        // panics (like one whose index is out of bounds) never reaches them
+        let emitted = self.inner.diffs.has_subscribers().then(|| diff.clone());
+        diff.apply_to(&mut self.inner.values.borrow_mut());
+        if let Some(diff) = emitted {
+            self.inner.diffs.emit(&diff);
+        }
+        self.inner.diffs.notify();
+    }
+}
+
+impl<T> Clone for SignalVec<T>
+where
+    T: 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Shared::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Debug for SignalVec<T>
+where
+    T: Debug + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SignalVec")
+            .field(&self.inner.values)
+            .finish()
+    }
+}
+
+impl<T> FnOnce<()> for SignalVec<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    type Output = Vec<T>;
+
+    extern "rust-call" fn call_once(self, _args: ()) -> Self::Output {
+        self.get()
+    }
+}
+
+impl<T> FnMut<()> for SignalVec<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    extern "rust-call" fn call_mut(&mut self, _args: ()) -> Self::Output {
+        self.get()
+    }
+}
+
+impl<T> Fn<()> for SignalVec<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    extern "rust-call" fn call(&self, _args: ()) -> Self::Output {
+        self.get()
+    }
+}
+
+/// The changes made to a [SignalVec] since they were last taken. See [SignalVec::subscribe].
+pub struct VecDiffs<T>
+where
+    T: 'static,
+{
+    inner: Shared<SignalVecInner<T>>,
+    id: usize,
+}
+
+impl<T> VecDiffs<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    /// Takes the changes made since the last call, and subscribes the running effect to
+    /// further changes.
+    pub fn take(&self) -> Vec<VecDiff<T>> {
+        self.inner.diffs.track();
+        self.inner.diffs.take(self.id)
+    }
+}
+
+/// Creates a [SignalMap], a reactive [HashMap] whose changes can be observed one at a
+/// time as [MapDiff]s.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// let users = create_signal_map(cx, Default::default());
+/// let diffs = users.subscribe(cx);
+///
+/// users.insert(1, "Alice");
+/// users.remove(&1);
+/// assert_eq!(
+///   diffs.take(),
+///   vec![
+///     MapDiff::Replace(Default::default()),
+///     MapDiff::Insert { key: 1, value: "Alice" },
+///     MapDiff::Remove { key: 1 }
+///   ]
+/// );
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_signal_map<K, V>(cx: Scope, values: HashMap<K, V>) -> SignalMap<K, V>
+where
+    K: Hash + Eq + Clone + ThreadSafe + 'static,
+    V: Clone + ThreadSafe + 'static,
+{
+    SignalMap {
+        inner: Shared::new(SignalMapInner {
+            values: RefCell::new(values),
+            diffs: DiffQueues::new(cx),
+        }),
+    }
+}
+
+/// A change to a [SignalMap].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MapDiff<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Replaces the whole map.
+    Replace(HashMap<K, V>),
+    /// Inserts a value, or replaces the existing value for `key`.
+    Insert { key: K, value: V },
+    /// Removes the value for `key`.
+    Remove { key: K },
+    /// Removes every value.
+    Clear,
+}
+
+impl<K, V> MapDiff<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Makes the same change to another [HashMap].
+    pub fn apply_to(self, values: &mut HashMap<K, V>) {
+        match self {
+            MapDiff::Replace(new_values) => *values = new_values,
+            MapDiff::Insert { key, value } => {
+                values.insert(key, value);
+            }
+            MapDiff::Remove { key } => {
+                values.remove(&key);
+            }
+            MapDiff::Clear => values.clear(),
+        }
+    }
+}
+
+/// A reactive [HashMap] whose changes are emitted as [MapDiff]s. See [create_signal_map].
+pub struct SignalMap<K, V>
+where
+    K: Hash + Eq + 'static,
+    V: 'static,
+{
+    inner: Shared<SignalMapInner<K, V>>,
+}
+
+struct SignalMapInner<K, V>
+where
+    K: Hash + Eq,
+{
+    values: RefCell<HashMap<K, V>>,
+    diffs: DiffQueues<MapDiff<K, V>>,
+}
+
+impl<K, V> SignalMap<K, V>
+where
+    K: Hash + Eq + Clone + ThreadSafe + 'static,
+    V: Clone + ThreadSafe + 'static,
+{
+    /// Applies a function to the current map, and subscribes the running effect to changes.
+    pub fn with<U>(&self, f: impl FnOnce(&HashMap<K, V>) -> U) -> U {
+        self.inner.diffs.track();
+        f(&self.inner.values.borrow())
+    }
+
+    /// Clones and returns the value for `key`, and subscribes the running effect to changes.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.with(|values| values.get(key).cloned())
+    }
+
+    pub fn len(&self) -> usize {
+        self.with(HashMap::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.with(HashMap::is_empty)
+    }
+
+    /// Returns a [MapDiffs] that collects every change made to the map from now on,
+    /// starting with a [MapDiff::Replace] holding its current contents. It stops
+    /// collecting changes when `cx` is disposed.
+    pub fn subscribe(&self, cx: Scope) -> MapDiffs<K, V> {
+        let current = MapDiff::Replace(self.inner.values.borrow().clone());
+        let id = self.inner.diffs.subscribe(current);
+        on_cleanup(cx, {
+            let inner = Shared::clone(&self.inner);
+            move || inner.diffs.unsubscribe(id)
+        });
+        MapDiffs {
+            inner: Shared::clone(&self.inner),
+            id,
+        }
+    }
+
+    /// Inserts a value, returning the value it replaced, if any.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let prev = self.inner.values.borrow().get(&key).cloned();
+        self.apply(MapDiff::Insert { key, value });
+        prev
+    }
+
+    /// Removes a value, returning it if it was in the map.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let prev = self.inner.values.borrow().get(key).cloned();
+        if prev.is_some() {
+            self.apply(MapDiff::Remove { key: key.clone() });
+        }
+        prev
+    }
+
+    pub fn clear(&self) {
+        self.apply(MapDiff::Clear);
+    }
+
+    /// Replaces the whole map.
+    pub fn replace(&self, values: HashMap<K, V>) {
+        self.apply(MapDiff::Replace(values));
+    }
+
+    fn apply(&self, diff: MapDiff<K, V>) {
+        // subscribers only see the change once it has been applied, so a change that
+        // panics (like one whose index is out of bounds) never reaches them
+        let emitted = self.inner.diffs.has_subscribers().then(|| diff.clone());
+        diff.apply_to(&mut self.inner.values.borrow_mut());
+        if let Some(diff) = emitted {
+            self.inner.diffs.emit(&diff);
+        }
+        self.inner.diffs.notify();
+    }
+}
+
+impl<K, V> Clone for SignalMap<K, V>
+where
+    K: Hash + Eq + 'static,
+    V: 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Shared::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V> Debug for SignalMap<K, V>
+where
+    K: Hash + Eq + Debug + 'static,
+    V: Debug + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SignalMap")
+            .field(&self.inner.values)
+            .finish()
+    }
+}
+
+/// The changes made to a [SignalMap] since they were last taken. See [SignalMap::subscribe].
+pub struct MapDiffs<K, V>
+where
+    K: Hash + Eq + 'static,
+    V: 'static,
+{
+    inner: Shared<SignalMapInner<K, V>>,
+    id: usize,
+}
+
+impl<K, V> MapDiffs<K, V>
+where
+    K: Hash + Eq + Clone + ThreadSafe + 'static,
+    V: Clone + ThreadSafe + 'static,
+{
+    /// Takes the changes made since the last call, and subscribes the running effect to
+    /// further changes.
+    pub fn take(&self) -> Vec<MapDiff<K, V>> {
+        self.inner.diffs.track();
+        self.inner.diffs.take(self.id)
+    }
+}
+
+/// The changes not yet taken by each subscriber to a collection, and a signal that
+/// notifies readers of the collection when it changes.
+struct DiffQueues<D> {
+    trigger: (ReadSignal<()>, WriteSignal<()>),
+    queues: RefCell<HashMap<usize, Vec<D>>>,
+    next_id: Cell<usize>,
+}
+
+impl<D> DiffQueues<D>
+where
+    D: Clone,
+{
+    fn new(cx: Scope) -> Self {
+        Self {
+            trigger: create_signal(cx, ()),
+            queues: Default::default(),
+            next_id: Cell::new(0),
+        }
+    }
+
+    fn track(&self) {
+        self.trigger.0.with(|_| ());
+    }
+
+    fn notify(&self) {
+        self.trigger.1.update(|_| ());
+    }
+
+    fn subscribe(&self, initial: D) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.queues.borrow_mut().insert(id, vec![initial]);
+        id
+    }
+
+    fn unsubscribe(&self, id: usize) {
+        self.queues.borrow_mut().remove(&id);
+    }
+
+    fn has_subscribers(&self) -> bool {
+        !self.queues.borrow().is_empty()
+    }
+
+    fn emit(&self, diff: &D) {
+        for queue in self.queues.borrow_mut().values_mut() {
+            queue.push(diff.clone());
+        }
+    }
+
+    fn take(&self, id: usize) -> Vec<D> {
+        self.queues
+            .borrow_mut()
+            .get_mut(&id)
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
@@ -1,9 +1,92 @@
 use std::{
-    any::{Any, TypeId},
+    any::TypeId,
     collections::HashMap,
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
-use crate::Scope;
+use crate::{sync::AnyValue, with_runtime, Scope, ThreadSafe};
+
+/// Identifies a context value within a [Scope]: either by its type, for
+/// [provide_context], or by a [ContextKey], for [provide_context_with_key].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum ContextId {
+    Type(TypeId),
+    Key(usize),
+}
+
+/// A key for a context value of type `T`, created with [create_context_key].
+///
+/// Each key is distinct, so several values of the same type can be provided at once
+/// without wrapping each of them in a newtype.
+pub struct ContextKey<T> {
+    id: usize,
+    ty: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for ContextKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ContextKey<T> {}
+
+impl<T> PartialEq for ContextKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for ContextKey<T> {}
+
+impl<T> std::hash::Hash for ContextKey<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for ContextKey<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextKey")
+            .field("id", &self.id)
+            .field("ty", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+/// Creates a new key for a context value of type `T`, which is different from every
+/// other key. Values are provided with [provide_context_with_key] and read with
+/// [use_context_with_key].
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// let first_name = create_context_key::<String>();
+/// let last_name = create_context_key::<String>();
+///
+/// provide_context_with_key(cx, first_name, "Greg".to_string());
+/// provide_context_with_key(cx, last_name, "Johnston".to_string());
+///
+/// cx.child_scope(|cx| {
+///   assert_eq!(use_context_with_key(cx, first_name).as_deref(), Some("Greg"));
+///   assert_eq!(use_context_with_key(cx, last_name).as_deref(), Some("Johnston"));
+///   // keyed values don't replace or provide a value by type
+///   assert_eq!(use_context::<String>(cx), None);
+/// });
+/// # }).dispose();
+/// ```
+pub fn create_context_key<T>() -> ContextKey<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    // keys can be moved between runtimes, so they're unique across all of them
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    ContextKey {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        ty: PhantomData,
+    }
+}
 
 /// Provides a context value of type `T` to the current reactive [Scope](crate::Scope)
 /// and all of its descendants. This can be consumed using [use_context](crate::use_context).
@@ -45,12 +128,30 @@ use crate::Scope;
 /// ```
 pub fn provide_context<T>(cx: Scope, value: T)
 where
-    T: Clone + 'static,
+    T: Clone + ThreadSafe + 'static,
+{
+    insert_context(cx, ContextId::Type(TypeId::of::<T>()), value);
+}
+
+/// Provides a context value to the current reactive [Scope](crate::Scope) and all of its
+/// descendants under the given [ContextKey], rather than under its type. This can be
+/// consumed using [use_context_with_key](crate::use_context_with_key).
+pub fn provide_context_with_key<T>(cx: Scope, key: ContextKey<T>, value: T)
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    insert_context(cx, ContextId::Key(key.id), value);
+}
+
+fn insert_context<T>(cx: Scope, id: ContextId, value: T)
+where
+    T: Clone + ThreadSafe + 'static,
 {
-    let id = value.type_id();
-    let mut contexts = cx.runtime.scope_contexts.borrow_mut();
-    let context = contexts.entry(cx.id).unwrap().or_insert_with(HashMap::new);
-    context.insert(id, Box::new(value) as Box<dyn Any>);
+    with_runtime(cx.runtime, |runtime| {
+        let mut contexts = runtime.scope_contexts.borrow_mut();
+        let context = contexts.entry(cx.id).unwrap().or_insert_with(HashMap::new);
+        context.insert(id, Box::new(value) as Box<AnyValue>);
+    });
 }
 
 /// Extracts a context value of type `T` from the reactive system by traversing
@@ -97,26 +198,68 @@ pub fn use_context<T>(cx: Scope) -> Option<T>
 where
     T: Clone + 'static,
 {
-    let id = TypeId::of::<T>();
-    let local_value = {
-        let contexts = cx.runtime.scope_contexts.borrow();
-        let context = contexts.get(cx.id);
-        context
-            .and_then(|context| context.get(&id).and_then(|val| val.downcast_ref::<T>()))
-            .cloned()
-    };
-    match local_value {
-        Some(val) => Some(val),
-        None => cx
-            .runtime
-            .scope_parents
-            .borrow()
-            .get(cx.id)
-            .and_then(|parent| {
-                use_context::<T>(Scope {
-                    runtime: cx.runtime,
-                    id: *parent,
-                })
-            }),
+    find_context(cx, ContextId::Type(TypeId::of::<T>()))
+}
+
+/// Extracts the context value provided under the given [ContextKey], by traversing the
+/// reactive system upwards from the current [Scope](crate::Scope), like
+/// [use_context](crate::use_context). The value should have been provided using
+/// [provide_context_with_key](crate::provide_context_with_key).
+pub fn use_context_with_key<T>(cx: Scope, key: ContextKey<T>) -> Option<T>
+where
+    T: Clone + 'static,
+{
+    find_context(cx, ContextId::Key(key.id))
+}
+
+/// Extracts a context value of type `T`, like [use_context](crate::use_context), for a
+/// value that is always expected to have been provided.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// provide_context(cx, 42_i32);
+/// assert_eq!(expect_context::<i32>(cx), 42);
+/// # }).dispose();
+/// ```
+///
+/// # Panics
+/// Panics if no value of type `T` has been provided to this scope or any of its parents.
+/// The message names the missing type and the place `expect_context` was called from.
+#[track_caller]
+pub fn expect_context<T>(cx: Scope) -> T
+where
+    T: Clone + 'static,
+{
+    let location = std::panic::Location::caller();
+    use_context(cx).unwrap_or_else(|| {
+        panic!(
+            "expected a context of type {} to have been provided, but none was found \
+             (called at {location})",
+            std::any::type_name::<T>()
+        )
+    })
+}
+
+fn find_context<T>(cx: Scope, id: ContextId) -> Option<T>
+where
+    T: Clone + 'static,
+{
+    let mut scope = Some(cx.id);
+    while let Some(scope_id) = scope {
+        let (local_value, parent) = with_runtime(cx.runtime, |runtime| {
+            let contexts = runtime.scope_contexts.borrow();
+            let local_value = contexts
+                .get(scope_id)
+                .and_then(|context| context.get(&id).and_then(|val| val.downcast_ref::<T>()))
+                .cloned();
+            let parent = runtime.scope_parents.borrow().get(scope_id).copied();
+            (local_value, parent)
+        });
+        if local_value.is_some() {
+            return local_value;
+        }
+        scope = parent;
     }
+    None
 }
@@ -1,4 +1,4 @@
-use crate::{Runtime, Scope, ScopeProperty};
+use crate::{with_runtime, Runtime, Scope, ScopeProperty, ThreadSafe};
 use std::fmt::Debug;
 
 /// Effects run a certain chunk of code whenever the signals they depend on change.
@@ -17,6 +17,13 @@ use std::fmt::Debug;
 /// By default, effects **do not run on the server**. This means you can call browser-specific
 /// APIs within the effect function without causing issues. If you need an effect to run on
 /// the server, use [create_isomorphic_effect].
+///
+/// Anything the effect function creates with the scope the effect was created in
+/// (signals, memos, nested effects, resources, and cleanup functions) belongs to that run
+/// of the effect: it is disposed of before the effect runs again, and when the effect itself
+/// is disposed. This means that state created for a conditionally-rendered part of the UI
+/// doesn’t pile up every time the condition changes. To keep something around for longer,
+/// create it inside [untrack](crate::untrack), or with another scope.
 /// ```
 /// # use leptos_reactive::*;
 /// # use log::*;
@@ -42,9 +49,10 @@ use std::fmt::Debug;
 /// # assert_eq!(b(), 2);
 /// # }).dispose();
 /// ```
-pub fn create_effect<T>(cx: Scope, f: impl FnMut(Option<T>) -> T + 'static)
+#[track_caller]
+pub fn create_effect<T>(cx: Scope, f: impl FnMut(Option<T>) -> T + ThreadSafe + 'static)
 where
-    T: Debug + 'static,
+    T: Debug + ThreadSafe + 'static,
 {
     #[cfg(not(feature = "ssr"))]
     create_isomorphic_effect(cx, f);
@@ -76,24 +84,38 @@ where
 /// });
 /// # assert_eq!(b(), 2);
 /// # }).dispose();
-pub fn create_isomorphic_effect<T>(cx: Scope, f: impl FnMut(Option<T>) -> T + 'static)
+#[track_caller]
+pub fn create_isomorphic_effect<T>(cx: Scope, f: impl FnMut(Option<T>) -> T + ThreadSafe + 'static)
 where
-    T: Debug + 'static,
+    T: Debug + ThreadSafe + 'static,
 {
-    let e = cx.runtime.create_effect(f);
+    let e = with_runtime(cx.runtime, |runtime| runtime.create_effect(cx.id, f));
     cx.with_scope_property(|prop| prop.push(ScopeProperty::Effect(e)))
 }
 
 #[doc(hidden)]
-pub fn create_render_effect<T>(cx: Scope, f: impl FnMut(Option<T>) -> T + 'static)
+#[track_caller]
+pub fn create_render_effect<T>(cx: Scope, f: impl FnMut(Option<T>) -> T + ThreadSafe + 'static)
 where
-    T: Debug + 'static,
+    T: Debug + ThreadSafe + 'static,
 {
     create_effect(cx, f);
 }
 
 slotmap::new_key_type! { pub struct EffectId; }
 
+/// Whether an effect or memo needs to re-run, as it is marked when its sources change.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum EffectState {
+    /// Up to date.
+    Clean,
+    /// One of its sources is a memo that may have changed, so those memos need to be
+    /// brought up to date before we know whether it needs to re-run.
+    Check,
+    /// One of its sources has changed, so it needs to re-run.
+    Dirty,
+}
+
 pub(crate) struct Effect<T, F>
 where
     T: 'static,
@@ -103,41 +125,43 @@ where
     pub(crate) value: Option<T>,
 }
 
-pub(crate) trait AnyEffect {
-    fn run(&mut self, id: EffectId, runtime: &Runtime);
+pub(crate) trait AnyEffect: ThreadSafe {
+    /// Runs the effect, returning `true` if its value has changed.
+    fn run(&mut self, id: EffectId, runtime: &Runtime) -> bool;
 }
 
 impl<T, F> AnyEffect for Effect<T, F>
 where
-    T: 'static,
-    F: FnMut(Option<T>) -> T,
+    T: ThreadSafe + 'static,
+    F: FnMut(Option<T>) -> T + ThreadSafe,
 {
-    fn run(&mut self, id: EffectId, runtime: &Runtime) {
+    fn run(&mut self, id: EffectId, runtime: &Runtime) -> bool {
         // clear previous dependencies
         id.cleanup(runtime);
 
-        // set this as the current observer
-        let prev_observer = runtime.observer.take();
-        runtime.observer.set(Some(id));
-
-        // run the effect
+        // run the effect, with this as the current observer
         let value = self.value.take();
-        let new_value = (self.f)(value);
+        let new_value = runtime.with_observer(Some(id), || (self.f)(value));
         self.value = Some(new_value);
 
-        // restore the previous observer
-        runtime.observer.set(prev_observer);
+        true
     }
 }
 
 impl EffectId {
-    pub(crate) fn run<T>(&self, runtime: &Runtime) {
+    pub(crate) fn run(&self, runtime: &Runtime) -> bool {
         let effect = {
             let effects = runtime.effects.borrow();
             effects.get(*self).cloned()
         };
         if let Some(effect) = effect {
-            effect.borrow_mut().run(*self, runtime);
+            // clean up after the previous run, and dispose of whatever it created
+            runtime.run_effect_cleanups(*self);
+            runtime.dispose_effect_scope(*self);
+
+            effect.borrow_mut().run(*self, runtime)
+        } else {
+            false
         }
     }
 
@@ -145,8 +169,8 @@ impl EffectId {
         let sources = runtime.effect_sources.borrow();
         if let Some(sources) = sources.get(*self) {
             let subs = runtime.signal_subscribers.borrow();
-            for source in sources.borrow().iter() {
-                if let Some(source) = subs.get(*source) {
+            for source in sources.borrow_mut().drain() {
+                if let Some(source) = subs.get(source) {
                     source.borrow_mut().remove(self);
                 }
             }
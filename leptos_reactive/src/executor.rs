@@ -0,0 +1,205 @@
+use std::{future::Future, pin::Pin};
+
+use crate::{
+    runtime::{try_with_runtime, RuntimeId},
+    sync::Shared,
+    Scope, ThreadSafe,
+};
+
+/// Runs the background work of a reactive runtime: the futures that load
+/// [Resource](crate::Resource)s, dispatch [Action](crate::Action)s, and run tasks spawned
+/// with [Scope::spawn], and the microtasks that update [SuspenseContext](crate::SuspenseContext)s.
+///
+/// By default, futures are spawned with [spawn_local](crate::spawn_local) and microtasks
+/// are queued with [queue_microtask](crate::queue_microtask). Installing an executor with
+/// [Scope::set_executor] replaces both for every scope in the same runtime.
+pub trait Executor: ThreadSafe {
+    /// Runs a future in the background, on the current thread.
+    fn spawn_local(&self, fut: Pin<Box<dyn Future<Output = ()>>>);
+
+    /// Runs a function once the current task is done, before any other task.
+    fn queue_microtask(&self, task: Box<dyn FnOnce()>);
+}
+
+impl Scope {
+    /// Installs an [Executor] for the runtime this scope belongs to. Work started after
+    /// this, by this scope or any other scope in the runtime, runs on the new executor.
+    ///
+    /// See [TestExecutor] for an example.
+    pub fn set_executor(&self, executor: impl Executor + 'static) {
+        _ = try_with_runtime(self.runtime, |runtime| {
+            *runtime.executor.borrow_mut() = Some(Shared::new(executor));
+        });
+    }
+}
+
+/// The executor installed for the runtime, if any.
+pub(crate) fn runtime_executor(runtime: RuntimeId) -> Option<Shared<dyn Executor>> {
+    try_with_runtime(runtime, |runtime| runtime.executor.borrow().clone())
+        .ok()
+        .flatten()
+}
+
+pub use test_executor::TestExecutor;
+
+mod test_executor {
+    use std::{
+        cell::RefCell,
+        collections::{HashMap, VecDeque},
+        future::Future,
+        pin::Pin,
+        rc::Rc,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use futures::{
+        executor::{LocalPool, LocalSpawner},
+        task::LocalSpawnExt,
+    };
+
+    use super::Executor;
+    use crate::sync::Shared;
+
+    /// A deterministic [Executor] for tests, which only does work when asked to.
+    ///
+    /// Spawned futures and queued microtasks wait until [run_until_stalled](Self::run_until_stalled)
+    /// is called, so the order in which resources load, suspense counters update, and
+    /// signals change can be controlled exactly in a plain `#[test]`, without an async runtime.
+    ///
+    /// With the `multithreaded` feature, the executor can be shared between threads, but each
+    /// thread has its own queue: work started on a thread waits for that thread to run it.
+    ///
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # create_scope(|cx| {
+    /// let executor = TestExecutor::new();
+    /// cx.set_executor(executor.clone());
+    ///
+    /// let (count, set_count) = create_signal(cx, 0);
+    /// cx.spawn(async move { set_count(1) });
+    ///
+    /// // nothing runs until the executor is driven
+    /// assert_eq!(count(), 0);
+    /// executor.run_until_stalled();
+    /// assert_eq!(count(), 1);
+    /// # }).dispose();
+    /// ```
+    #[derive(Clone)]
+    pub struct TestExecutor {
+        id: Shared<ExecutorId>,
+    }
+
+    /// Identifies a [TestExecutor] and its clones. Dropping the last clone drops whatever
+    /// is still queued for it on the current thread.
+    struct ExecutorId(usize);
+
+    /// The work queued for one executor on one thread.
+    struct Queue {
+        pool: RefCell<LocalPool>,
+        spawner: LocalSpawner,
+        microtasks: RefCell<VecDeque<Box<dyn FnOnce()>>>,
+    }
+
+    thread_local! {
+        static QUEUES: RefCell<HashMap<usize, Rc<Queue>>> = Default::default();
+    }
+
+    impl TestExecutor {
+        /// Creates an executor with nothing queued.
+        pub fn new() -> Self {
+            static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+            Self {
+                id: Shared::new(ExecutorId(NEXT_ID.fetch_add(1, Ordering::Relaxed))),
+            }
+        }
+
+        /// Runs every queued microtask, including any queued by the microtasks themselves,
+        /// without polling any futures.
+        pub fn flush_microtasks(&self) {
+            // the queue isn't borrowed while a task runs, so it can queue more
+            while let Some(task) = self.next_microtask() {
+                task();
+            }
+        }
+
+        /// The number of microtasks waiting for [flush_microtasks](Self::flush_microtasks).
+        pub fn pending_microtasks(&self) -> usize {
+            self.queue().microtasks.borrow().len()
+        }
+
+        /// Polls every future that can make progress, and flushes microtasks after each
+        /// round, until no future can make progress and no microtasks are left.
+        ///
+        /// Futures that are waiting on something outside the executor, like a channel that
+        /// hasn't been sent to yet, stay pending until this is called again.
+        ///
+        /// # Panics
+        /// Panics if called from inside a future that the executor is running.
+        pub fn run_until_stalled(&self) {
+            let queue = self.queue();
+            loop {
+                self.flush_microtasks();
+                queue.pool.borrow_mut().run_until_stalled();
+                if self.pending_microtasks() == 0 {
+                    break;
+                }
+            }
+        }
+
+        fn next_microtask(&self) -> Option<Box<dyn FnOnce()>> {
+            self.queue().microtasks.borrow_mut().pop_front()
+        }
+
+        /// This executor's queue on the current thread.
+        fn queue(&self) -> Rc<Queue> {
+            // cloned out of the map, so that running a task can queue more
+            QUEUES.with(|queues| {
+                let mut queues = queues.borrow_mut();
+                let queue = queues.entry(self.id.0).or_insert_with(|| {
+                    let pool = LocalPool::new();
+                    Rc::new(Queue {
+                        spawner: pool.spawner(),
+                        pool: RefCell::new(pool),
+                        microtasks: Default::default(),
+                    })
+                });
+                Rc::clone(queue)
+            })
+        }
+    }
+
+    impl Drop for ExecutorId {
+        fn drop(&mut self) {
+            // removed before it's dropped, in case dropping a task uses another executor
+            let queue = QUEUES.try_with(|queues| queues.borrow_mut().remove(&self.0));
+            drop(queue);
+        }
+    }
+
+    impl Default for TestExecutor {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl std::fmt::Debug for TestExecutor {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("TestExecutor")
+                .field("pending_microtasks", &self.pending_microtasks())
+                .finish()
+        }
+    }
+
+    impl Executor for TestExecutor {
+        fn spawn_local(&self, fut: Pin<Box<dyn Future<Output = ()>>>) {
+            self.queue()
+                .spawner
+                .spawn_local(fut)
+                .expect("the executor is never shut down");
+        }
+
+        fn queue_microtask(&self, task: Box<dyn FnOnce()>) {
+            self.queue().microtasks.borrow_mut().push_back(task);
+        }
+    }
+}
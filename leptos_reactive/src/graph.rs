@@ -0,0 +1,496 @@
+use crate::{
+    with_runtime, EffectId, Memo, ReadSignal, Runtime, RuntimeId, RwSignal, Scope, ScopeId,
+    ScopeProperty, SignalId, WriteSignal,
+};
+#[cfg(feature = "resource")]
+use crate::{Resource, ResourceId};
+use serde::Serialize;
+use slotmap::Key;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Write},
+    panic::Location,
+};
+
+/// A snapshot of everything that is currently alive in a reactive runtime (its scopes,
+/// signals, effects, and resources), and how they depend on one another.
+///
+/// Take one with [Scope::reactive_graph] and export it with [ReactiveGraph::to_json] or
+/// [ReactiveGraph::to_dot] (for [Graphviz](https://graphviz.org/)). Each effect records
+/// how many times it has run and which signal caused it to run most recently, which is
+/// usually what you need to work out why an effect re-ran.
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// let (count, set_count) = create_signal(cx, 0);
+/// let count = count.debug_name("count");
+/// let double = create_memo(cx, move |_| count() * 2).debug_name("double");
+/// create_isomorphic_effect(cx, move |_| {
+///   name_effect(cx, "log");
+///   println!("double = {}", double());
+/// });
+///
+/// set_count(1);
+///
+/// let graph = cx.reactive_graph();
+/// let log = graph.effects.iter().find(|effect| effect.name.as_deref() == Some("log")).unwrap();
+/// assert_eq!(log.runs, 2);
+///
+/// // it re-ran because `double` changed
+/// let trigger = graph.signal(log.triggered_by.unwrap()).unwrap();
+/// assert_eq!(trigger.name.as_deref(), Some("double"));
+///
+/// println!("{}", graph.to_dot());
+/// # }).dispose();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReactiveGraph {
+    pub scopes: Vec<ScopeNode>,
+    pub signals: Vec<SignalNode>,
+    pub effects: Vec<EffectNode>,
+    #[cfg(feature = "resource")]
+    pub resources: Vec<ResourceNode>,
+}
+
+/// A [Scope] in a [ReactiveGraph].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ScopeNode {
+    pub id: ScopeId,
+    pub name: Option<String>,
+    pub parent: Option<ScopeId>,
+}
+
+/// A signal in a [ReactiveGraph]. This includes the signals that hold the values of memos.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SignalNode {
+    pub id: SignalId,
+    pub name: Option<String>,
+    /// The scope that owns the signal.
+    pub scope: Option<ScopeId>,
+    /// The effects and memos that read the signal the last time they ran.
+    pub subscribers: Vec<EffectId>,
+    /// If this holds the value of a memo, the computation that updates it.
+    pub memo: Option<EffectId>,
+}
+
+/// An effect or a memo’s computation in a [ReactiveGraph].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct EffectNode {
+    pub id: EffectId,
+    pub name: Option<String>,
+    /// The scope that owns the effect.
+    pub scope: Option<ScopeId>,
+    /// The signals it read the last time it ran.
+    pub sources: Vec<SignalId>,
+    /// If this is a memo’s computation, the signal that holds the memo’s value.
+    pub memo: Option<SignalId>,
+    /// The number of times it has run.
+    pub runs: usize,
+    /// The signal whose change caused it to run most recently, if it has re-run.
+    pub triggered_by: Option<SignalId>,
+}
+
+/// A [Resource] in a [ReactiveGraph].
+#[cfg(feature = "resource")]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ResourceNode {
+    pub id: ResourceId,
+    pub name: Option<String>,
+    /// The scope that owns the resource.
+    pub scope: Option<ScopeId>,
+    /// The signal holding the value of the memo it loads from.
+    pub source: SignalId,
+    /// The signal holding its value.
+    pub value: SignalId,
+    /// The signal holding whether it is loading.
+    pub loading: SignalId,
+}
+
+impl ReactiveGraph {
+    pub fn scope(&self, id: ScopeId) -> Option<&ScopeNode> {
+        self.scopes.iter().find(|node| node.id == id)
+    }
+
+    pub fn signal(&self, id: SignalId) -> Option<&SignalNode> {
+        self.signals.iter().find(|node| node.id == id)
+    }
+
+    pub fn effect(&self, id: EffectId) -> Option<&EffectNode> {
+        self.effects.iter().find(|node| node.id == id)
+    }
+
+    #[cfg(feature = "resource")]
+    pub fn resource(&self, id: ResourceId) -> Option<&ResourceNode> {
+        self.resources.iter().find(|node| node.id == id)
+    }
+
+    /// Serializes the graph as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("couldn't serialize reactive graph")
+    }
+
+    /// Exports the graph in the [DOT](https://graphviz.org/doc/info/lang.html) language,
+    /// so that it can be drawn with Graphviz. Each scope is drawn as a box containing the
+    /// signals, effects, and resources it owns, along with its child scopes, and each arrow
+    /// points in the direction that changes flow.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph reactive_graph {\n");
+
+        let mut children: HashMap<Option<ScopeId>, Vec<&ScopeNode>> = HashMap::new();
+        for scope in &self.scopes {
+            let parent = scope.parent.filter(|parent| self.scope(*parent).is_some());
+            children.entry(parent).or_default().push(scope);
+        }
+        for root in children.get(&None).into_iter().flatten() {
+            self.write_scope(&mut dot, root, &children, 1);
+        }
+        // anything whose scope has been disposed
+        self.write_scope_contents(&mut dot, None, 1);
+
+        for signal in &self.signals {
+            for subscriber in &signal.subscribers {
+                edge(&mut dot, &node_id(signal.id), &node_id(*subscriber), None);
+            }
+        }
+        for effect in &self.effects {
+            if let Some(signal) = effect.memo {
+                edge(&mut dot, &node_id(effect.id), &node_id(signal), None);
+            }
+        }
+        #[cfg(feature = "resource")]
+        for resource in &self.resources {
+            let id = node_id(resource.id);
+            edge(&mut dot, &node_id(resource.source), &id, None);
+            edge(&mut dot, &id, &node_id(resource.value), Some("value"));
+            edge(&mut dot, &id, &node_id(resource.loading), Some("loading"));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_scope(
+        &self,
+        dot: &mut String,
+        scope: &ScopeNode,
+        children: &HashMap<Option<ScopeId>, Vec<&ScopeNode>>,
+        depth: usize,
+    ) {
+        let indent = "  ".repeat(depth);
+        let label = label(scope.name.as_deref(), "scope", scope.id);
+        _ = writeln!(dot, "{indent}subgraph \"cluster_{}\" {{", node_id(scope.id));
+        _ = writeln!(dot, "{indent}  label = {label};");
+        self.write_scope_contents(dot, Some(scope.id), depth + 1);
+        for child in children.get(&Some(scope.id)).into_iter().flatten() {
+            self.write_scope(dot, child, children, depth + 1);
+        }
+        _ = writeln!(dot, "{indent}}}");
+    }
+
+    fn write_scope_contents(&self, dot: &mut String, scope: Option<ScopeId>, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let owned_by = |owner: Option<ScopeId>| match scope {
+            Some(_) => owner == scope,
+            None => owner.and_then(|owner| self.scope(owner)).is_none(),
+        };
+
+        for signal in self.signals.iter().filter(|node| owned_by(node.scope)) {
+            let kind = if signal.memo.is_some() {
+                "memo"
+            } else {
+                "signal"
+            };
+            let label = label(signal.name.as_deref(), kind, signal.id);
+            _ = writeln!(dot, "{indent}\"{}\" [label = {label}];", node_id(signal.id));
+        }
+        for effect in self.effects.iter().filter(|node| owned_by(node.scope)) {
+            let (kind, shape) = match effect.memo {
+                Some(_) => ("memo computation", "diamond"),
+                None => ("effect", "box"),
+            };
+            let label = label(effect.name.as_deref(), kind, effect.id);
+            _ = writeln!(
+                dot,
+                "{indent}\"{}\" [label = {label}, shape = {shape}, xlabel = \"runs: {}\"];",
+                node_id(effect.id),
+                effect.runs
+            );
+        }
+        #[cfg(feature = "resource")]
+        for resource in self.resources.iter().filter(|node| owned_by(node.scope)) {
+            let label = label(resource.name.as_deref(), "resource", resource.id);
+            _ = writeln!(
+                dot,
+                "{indent}\"{}\" [label = {label}, shape = hexagon];",
+                node_id(resource.id)
+            );
+        }
+    }
+}
+
+/// The ID of a node in DOT output. Each kind of node has its own prefix, as the IDs of
+/// different kinds of nodes can be the same.
+fn node_id(node: impl Into<GraphNode>) -> String {
+    match node.into() {
+        GraphNode::Scope(id) => format!("scope_{:?}", id.data()),
+        GraphNode::Signal(id) => format!("signal_{:?}", id.data()),
+        GraphNode::Effect(id) => format!("effect_{:?}", id.data()),
+        #[cfg(feature = "resource")]
+        GraphNode::Resource(id) => format!("resource_{:?}", id.data()),
+    }
+}
+
+fn label(name: Option<&str>, kind: &str, id: impl Key) -> String {
+    let label = match name {
+        Some(name) => format!("{name} ({kind} {:?})", id.data()),
+        None => format!("{kind} {:?}", id.data()),
+    };
+    format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn edge(dot: &mut String, from: &str, to: &str, label: Option<&str>) {
+    match label {
+        Some(label) => _ = writeln!(dot, "  \"{from}\" -> \"{to}\" [label = \"{label}\"];"),
+        None => _ = writeln!(dot, "  \"{from}\" -> \"{to}\";"),
+    }
+}
+
+impl Scope {
+    /// Takes a snapshot of the whole reactive runtime this scope belongs to. See [ReactiveGraph].
+    pub fn reactive_graph(&self) -> ReactiveGraph {
+        with_runtime(self.runtime, |runtime| {
+            let names = runtime.debug_names.borrow();
+            let name = |node: GraphNode| names.get(&node).cloned();
+
+            // which scope owns each node
+            let mut owners = HashMap::new();
+            let mut scopes = Vec::new();
+            let parents = runtime.scope_parents.borrow();
+            for (id, properties) in runtime.scopes.borrow().iter() {
+                for property in properties.borrow().iter() {
+                    let node = match property {
+                        ScopeProperty::Signal(id) => GraphNode::Signal(*id),
+                        ScopeProperty::Effect(id) => GraphNode::Effect(*id),
+                        #[cfg(feature = "resource")]
+                        ScopeProperty::Resource(id) => GraphNode::Resource(*id),
+                        _ => continue,
+                    };
+                    owners.insert(node, id);
+                }
+                scopes.push(ScopeNode {
+                    id,
+                    name: name(GraphNode::Scope(id)),
+                    parent: parents.get(id).copied(),
+                });
+            }
+
+            let memos = runtime.memos.borrow();
+            let subscribers = runtime.signal_subscribers.borrow();
+            let signals = runtime
+                .signals
+                .borrow()
+                .keys()
+                .map(|id| {
+                    let mut subscribers = subscribers
+                        .get(id)
+                        .map(|subs| subs.borrow().iter().copied().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    subscribers.sort();
+                    SignalNode {
+                        id,
+                        name: name(GraphNode::Signal(id)),
+                        scope: owners.get(&GraphNode::Signal(id)).copied(),
+                        subscribers,
+                        memo: memos.get(id).copied(),
+                    }
+                })
+                .collect();
+
+            let memo_signals = runtime.memo_signals.borrow();
+            let sources = runtime.effect_sources.borrow();
+            let runs = runtime.effect_runs.borrow();
+            let effects = runtime
+                .effects
+                .borrow()
+                .keys()
+                .map(|id| {
+                    let mut sources = sources
+                        .get(id)
+                        .map(|sources| sources.borrow().iter().copied().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    sources.sort();
+                    let runs = runs.get(id).cloned().unwrap_or_default();
+                    EffectNode {
+                        id,
+                        name: name(GraphNode::Effect(id)),
+                        scope: owners.get(&GraphNode::Effect(id)).copied(),
+                        sources,
+                        memo: memo_signals.get(id).copied(),
+                        runs: runs.runs,
+                        triggered_by: runs.triggered_by,
+                    }
+                })
+                .collect();
+
+            #[cfg(feature = "resource")]
+            let resources = runtime
+                .resources
+                .borrow()
+                .iter()
+                .map(|(id, resource)| {
+                    let (source, value, loading) = resource.graph_edges();
+                    ResourceNode {
+                        id,
+                        name: name(GraphNode::Resource(id)),
+                        scope: owners.get(&GraphNode::Resource(id)).copied(),
+                        source,
+                        value,
+                        loading,
+                    }
+                })
+                .collect();
+
+            ReactiveGraph {
+                scopes,
+                signals,
+                effects,
+                #[cfg(feature = "resource")]
+                resources,
+            }
+        })
+    }
+}
+
+impl Runtime {
+    pub(crate) fn record_location(&self, node: GraphNode, location: &'static Location<'static>) {
+        self.locations.borrow_mut().insert(node, location);
+    }
+
+    /// Removes the name, location, and run count of something that has been disposed.
+    pub(crate) fn forget_debug_info(&self, node: GraphNode) {
+        self.debug_names.borrow_mut().remove(&node);
+        self.locations.borrow_mut().remove(&node);
+        if let GraphNode::Effect(id) = node {
+            self.effect_runs.borrow_mut().remove(id);
+        }
+    }
+}
+
+/// Gives part of the reactive graph a name, which is used when it is inspected with
+/// [Scope::reactive_graph].
+pub trait DebugName {
+    /// Sets the name, returning `self` so it can be called where the value is created.
+    fn debug_name(self, name: impl Into<String>) -> Self;
+}
+
+impl DebugName for Scope {
+    fn debug_name(self, name: impl Into<String>) -> Self {
+        set_name(self.runtime, GraphNode::Scope(self.id), name.into());
+        self
+    }
+}
+
+impl<T> DebugName for ReadSignal<T> {
+    fn debug_name(self, name: impl Into<String>) -> Self {
+        set_name(self.runtime, GraphNode::Signal(self.id), name.into());
+        self
+    }
+}
+
+impl<T> DebugName for WriteSignal<T> {
+    fn debug_name(self, name: impl Into<String>) -> Self {
+        set_name(self.runtime, GraphNode::Signal(self.id), name.into());
+        self
+    }
+}
+
+impl<T> DebugName for RwSignal<T> {
+    fn debug_name(self, name: impl Into<String>) -> Self {
+        set_name(self.runtime, GraphNode::Signal(self.id), name.into());
+        self
+    }
+}
+
+impl<T> DebugName for Memo<T> {
+    /// Names both the signal that holds the memo’s value and the computation that updates it.
+    fn debug_name(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        let computation = with_runtime(self.0.runtime, |runtime| {
+            runtime.memos.borrow().get(self.0.id).copied()
+        });
+        if let Some(computation) = computation {
+            set_name(self.0.runtime, GraphNode::Effect(computation), name.clone());
+        }
+        set_name(self.0.runtime, GraphNode::Signal(self.0.id), name);
+        self
+    }
+}
+
+#[cfg(feature = "resource")]
+impl<S, T> DebugName for Resource<S, T>
+where
+    S: Debug + Clone + 'static,
+    T: Debug + Clone + 'static,
+{
+    fn debug_name(self, name: impl Into<String>) -> Self {
+        set_name(self.runtime, GraphNode::Resource(self.id), name.into());
+        self
+    }
+}
+
+/// Names the effect that is currently running, for inspecting it with
+/// [Scope::reactive_graph]. Effects don’t return a handle, so call this inside the effect.
+pub fn name_effect(cx: Scope, name: impl Into<String>) {
+    let observer = with_runtime(cx.runtime, |runtime| runtime.observer.get());
+    if let Some(effect) = observer {
+        set_name(cx.runtime, GraphNode::Effect(effect), name.into());
+    }
+}
+
+fn set_name(runtime: RuntimeId, node: GraphNode, name: String) {
+    with_runtime(runtime, |runtime| {
+        runtime.debug_names.borrow_mut().insert(node, name);
+    })
+}
+
+/// Anything in the reactive graph that can be given a [DebugName].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum GraphNode {
+    Scope(ScopeId),
+    Signal(SignalId),
+    Effect(EffectId),
+    #[cfg(feature = "resource")]
+    Resource(ResourceId),
+}
+
+impl From<ScopeId> for GraphNode {
+    fn from(id: ScopeId) -> Self {
+        Self::Scope(id)
+    }
+}
+
+impl From<SignalId> for GraphNode {
+    fn from(id: SignalId) -> Self {
+        Self::Signal(id)
+    }
+}
+
+impl From<EffectId> for GraphNode {
+    fn from(id: EffectId) -> Self {
+        Self::Effect(id)
+    }
+}
+
+#[cfg(feature = "resource")]
+impl From<ResourceId> for GraphNode {
+    fn from(id: ResourceId) -> Self {
+        Self::Resource(id)
+    }
+}
+
+/// How often an effect or memo has run, and why.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct EffectRuns {
+    pub runs: usize,
+    pub triggered_by: Option<SignalId>,
+}
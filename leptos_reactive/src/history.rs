@@ -0,0 +1,319 @@
+use std::{collections::VecDeque, fmt::Debug, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    batch, create_memo, create_signal, now, Memo, ReadSignal, Scope, ThreadSafe, WriteSignal,
+};
+
+/// Creates a [HistorySignal], a signal that remembers its previous values so that
+/// updates can be undone and redone.
+///
+/// Up to `capacity` previous values are kept; once there are more, the oldest are dropped.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// let text = create_history_signal(cx, String::new(), 100);
+/// assert!(!text.can_undo());
+///
+/// text.set("Hello".to_string());
+/// text.update(|text| text.push_str(", world!"));
+/// assert_eq!(text(), "Hello, world!");
+///
+/// text.undo();
+/// assert_eq!(text(), "Hello");
+/// assert!(text.can_redo());
+///
+/// text.redo();
+/// assert_eq!(text(), "Hello, world!");
+///
+/// // a new update can't be redone past
+/// text.undo();
+/// text.set("Goodbye".to_string());
+/// assert!(!text.can_redo());
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_history_signal<T>(cx: Scope, initial: T, capacity: usize) -> HistorySignal<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    let (value, set_value) = create_signal(cx, initial);
+    let (history, set_history) = create_signal(
+        cx,
+        History {
+            past: VecDeque::new(),
+            future: Vec::new(),
+            capacity,
+            coalesce: None,
+            last_recorded: None,
+        },
+    );
+    let can_undo = create_memo(cx, move |_| {
+        history.with(|history| !history.past.is_empty())
+    });
+    let can_redo = create_memo(cx, move |_| {
+        history.with(|history| !history.future.is_empty())
+    });
+    HistorySignal {
+        cx,
+        value,
+        set_value,
+        history,
+        set_history,
+        can_undo,
+        can_redo,
+    }
+}
+
+/// A signal that records each update, so that it can be undone and redone.
+/// See [create_history_signal].
+///
+/// Reading it with [with](HistorySignal::with) or [get](HistorySignal::get) behaves like
+/// reading a [ReadSignal], and [can_undo](HistorySignal::can_undo) and
+/// [can_redo](HistorySignal::can_redo) can be read reactively, for example to disable
+/// an “Undo” button.
+pub struct HistorySignal<T>
+where
+    T: 'static,
+{
+    cx: Scope,
+    value: ReadSignal<T>,
+    set_value: WriteSignal<T>,
+    history: ReadSignal<History<T>>,
+    set_history: WriteSignal<History<T>>,
+    can_undo: Memo<bool>,
+    can_redo: Memo<bool>,
+}
+
+#[derive(Clone)]
+struct History<T> {
+    past: VecDeque<T>,
+    // the next value to redo is at the end
+    future: Vec<T>,
+    capacity: usize,
+    coalesce: Option<Duration>,
+    last_recorded: Option<Duration>,
+}
+
+impl<T> History<T> {
+    fn record(&mut self, previous: T) {
+        let now = now();
+        let coalesced = match (self.coalesce, self.last_recorded) {
+            (Some(window), Some(last_recorded)) => now.saturating_sub(last_recorded) < window,
+            _ => false,
+        };
+        self.last_recorded = Some(now);
+        if !coalesced {
+            self.past.push_back(previous);
+            self.truncate();
+        }
+        self.future.clear();
+    }
+
+    fn truncate(&mut self) {
+        while self.past.len() > self.capacity {
+            self.past.pop_front();
+        }
+    }
+}
+
+/// The state of a [HistorySignal] at some point in time, which can be put back with
+/// [HistorySignal::restore].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistorySnapshot<T> {
+    /// The values that can be undone, from oldest to newest.
+    pub past: Vec<T>,
+    /// The current value.
+    pub present: T,
+    /// The values that can be redone, starting with the next one.
+    pub future: Vec<T>,
+}
+
+impl<T> HistorySignal<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    /// Merges updates made within `window` of the previous update into a single step, so
+    /// that, for example, typing a word can be undone all at once rather than a
+    /// character at a time.
+    pub fn coalesce(self, window: Duration) -> Self {
+        self.set_history
+            .update(|history| history.coalesce = Some(window));
+        self
+    }
+
+    /// Applies a function to the current value, and subscribes the running effect to changes.
+    pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.value.with(f)
+    }
+
+    /// Clones and returns the current value, and subscribes the running effect to changes.
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Applies a function to the current value, without subscribing the running effect.
+    pub fn with_untracked<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.value.with_untracked(f)
+    }
+
+    /// Clones and returns the current value, without subscribing the running effect.
+    pub fn get_untracked(&self) -> T {
+        self.value.get_untracked()
+    }
+
+    /// Updates the value, recording the previous value so that it can be undone.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let previous = self.value.get_untracked();
+        batch(self.cx, || {
+            self.set_history.update(|history| history.record(previous));
+            self.set_value.update(f);
+        });
+    }
+
+    /// Sets the value, recording the previous value so that it can be undone.
+    pub fn set(&self, value: T) {
+        self.update(move |n| *n = value);
+    }
+
+    /// Whether there is an update to [undo](HistorySignal::undo). This subscribes the
+    /// running effect to changes.
+    pub fn can_undo(&self) -> bool {
+        self.can_undo.get()
+    }
+
+    /// Whether there is an undone update to [redo](HistorySignal::redo). This subscribes
+    /// the running effect to changes.
+    pub fn can_redo(&self) -> bool {
+        self.can_redo.get()
+    }
+
+    /// Goes back to the value before the last update, if there is one.
+    pub fn undo(&self) {
+        if self
+            .history
+            .with_untracked(|history| history.past.is_empty())
+        {
+            return;
+        }
+        let current = self.value.get_untracked();
+        batch(self.cx, || {
+            let mut previous = None;
+            self.set_history.update(|history| {
+                previous = history.past.pop_back();
+                history.future.push(current);
+                // the next update starts a new step, rather than changing the undone one
+                history.last_recorded = None;
+            });
+            if let Some(previous) = previous {
+                self.set_value.update(move |value| *value = previous);
+            }
+        });
+    }
+
+    /// Goes forward to the value before the last [undo](HistorySignal::undo), if there is one.
+    pub fn redo(&self) {
+        if self
+            .history
+            .with_untracked(|history| history.future.is_empty())
+        {
+            return;
+        }
+        let current = self.value.get_untracked();
+        batch(self.cx, || {
+            let mut next = None;
+            self.set_history.update(|history| {
+                next = history.future.pop();
+                history.past.push_back(current);
+                history.truncate();
+                history.last_recorded = None;
+            });
+            if let Some(next) = next {
+                self.set_value.update(move |value| *value = next);
+            }
+        });
+    }
+
+    /// Copies the current value along with everything that can be undone and redone,
+    /// and subscribes the running effect to changes.
+    pub fn snapshot(&self) -> HistorySnapshot<T> {
+        self.history.with(|history| HistorySnapshot {
+            past: history.past.iter().cloned().collect(),
+            present: self.value.get(),
+            future: history.future.iter().rev().cloned().collect(),
+        })
+    }
+
+    /// Puts back the value and history from a [snapshot](HistorySignal::snapshot).
+    /// If the snapshot holds more than `capacity` values to undo, the oldest are dropped.
+    pub fn restore(&self, snapshot: HistorySnapshot<T>) {
+        let HistorySnapshot {
+            past,
+            present,
+            mut future,
+        } = snapshot;
+        future.reverse();
+        batch(self.cx, || {
+            self.set_history.update(|history| {
+                history.past = past.into();
+                history.truncate();
+                history.future = future;
+                history.last_recorded = None;
+            });
+            self.set_value.update(move |value| *value = present);
+        });
+    }
+}
+
+impl<T> Clone for HistorySignal<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for HistorySignal<T> where T: Clone {}
+
+impl<T> Debug for HistorySignal<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistorySignal")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<T> FnOnce<()> for HistorySignal<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    type Output = T;
+
+    extern "rust-call" fn call_once(self, _args: ()) -> Self::Output {
+        self.get()
+    }
+}
+
+impl<T> FnMut<()> for HistorySignal<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    extern "rust-call" fn call_mut(&mut self, _args: ()) -> Self::Output {
+        self.get()
+    }
+}
+
+impl<T> Fn<()> for HistorySignal<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    extern "rust-call" fn call(&self, _args: ()) -> Self::Output {
+        self.get()
+    }
+}
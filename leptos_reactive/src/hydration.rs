@@ -1,7 +1,10 @@
+#[cfg(any(feature = "ssr"))]
+use std::collections::HashMap;
 #[cfg(any(feature = "hydrate"))]
 use std::collections::{HashMap, HashSet};
-#[cfg(any(feature = "ssr"))]
-use std::{collections::HashMap, future::Future, pin::Pin};
+
+#[cfg(feature = "ssr")]
+use crate::PinnedFuture;
 
 #[cfg(any(feature = "hydrate"))]
 use crate::{ResourceId, Scope};
@@ -20,9 +23,15 @@ pub struct SharedContext {
     #[cfg(feature = "hydrate")]
     pub resolved_resources: HashMap<ResourceId, String>,
     #[cfg(feature = "ssr")]
-    pub pending_fragments: HashMap<String, Pin<Box<dyn Future<Output = String>>>>,
+    pub pending_fragments: HashMap<String, PinnedFuture<String>>,
 }
 
+// SAFETY: the only field that isn't `Sync` is `pending_fragments`, and its futures can only
+// be polled (or otherwise used) through a mutable reference, so sharing `&SharedContext`
+// between threads is safe.
+#[cfg(all(feature = "ssr", feature = "multithreaded"))]
+unsafe impl Sync for SharedContext {}
+
 impl std::fmt::Debug for SharedContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SharedContext").finish()
@@ -2,6 +2,7 @@
 #![feature(let_chains)]
 #![feature(unboxed_closures)]
 #![feature(test)]
+#![cfg_attr(feature = "multithreaded", feature(reentrant_lock))]
 
 //! The reactive system for the [Leptos](https://docs.rs/leptos/latest/leptos/) Web framework.
 //!
@@ -64,32 +65,69 @@
 //! });
 //! ```
 
+mod action;
+#[cfg(feature = "debug")]
+mod allocations;
+mod collections;
 mod context;
 mod effect;
+mod executor;
+#[cfg(feature = "debug")]
+mod graph;
+mod history;
 mod hydration;
 mod memo;
-
+#[cfg(feature = "resource")]
+mod query;
 #[cfg(feature = "resource")]
 mod resource;
 mod runtime;
 mod scope;
 mod selector;
+#[cfg(feature = "resource")]
+mod serialization;
 mod signal;
 mod spawn;
+mod store;
+mod stream;
 mod suspense;
+mod sync;
+#[cfg(feature = "transition")]
+mod transition;
 
+pub use action::*;
+#[cfg(feature = "debug")]
+pub use allocations::*;
+pub use collections::*;
 pub use context::*;
 pub use effect::*;
+pub use executor::*;
+#[cfg(feature = "debug")]
+pub use graph::*;
+pub use history::*;
 pub use memo::*;
-
+#[cfg(feature = "resource")]
+pub use query::*;
 #[cfg(feature = "resource")]
 pub use resource::*;
 use runtime::*;
 pub use scope::*;
 pub use selector::*;
+#[cfg(feature = "resource")]
+pub use serialization::*;
 pub use signal::*;
 pub use spawn::*;
+pub use store::*;
+pub use stream::*;
 pub use suspense::*;
+pub use sync::{MaybeSend, PinnedFuture, ThreadSafe};
+#[cfg(feature = "transition")]
+pub use transition::*;
+
+#[cfg(all(feature = "multithreaded", any(feature = "csr", feature = "hydrate")))]
+compile_error!(
+    "the `multithreaded` feature is only supported on the server, not with `csr` or `hydrate`"
+);
 
 #[doc(hidden)]
 #[macro_export]
@@ -108,7 +146,7 @@ macro_rules! debug_warn {
 
 extern crate test;
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "multithreaded")))]
 mod tests {
     use test::Bencher;
 
@@ -145,6 +183,85 @@ mod tests {
         });
     }
 
+    #[bench]
+    fn create_and_update_1000_diamonds(b: &mut Bencher) {
+        use crate::{create_effect, create_memo, create_scope, create_signal};
+
+        b.iter(|| {
+            create_scope(|cx| {
+                let acc = Rc::new(Cell::new(0));
+                let runs = Rc::new(Cell::new(0));
+                let sigs = (0..1000).map(|n| create_signal(cx, n)).collect::<Vec<_>>();
+                let writes = sigs.iter().map(|(_, w)| *w).collect::<Vec<_>>();
+
+                // each signal feeds two memos, which are joined again by a third
+                let diamonds = sigs
+                    .iter()
+                    .map(|(r, _)| {
+                        let r = *r;
+                        let left = create_memo(cx, move |_| r.get() * 2);
+                        let right = create_memo(cx, move |_| r.get() * 3);
+                        create_memo(cx, move |_| left.get() + right.get())
+                    })
+                    .collect::<Vec<_>>();
+                let memo = create_memo(cx, move |_| diamonds.iter().map(|d| d.get()).sum::<i32>());
+                assert_eq!(memo(), 2497500);
+                create_effect(cx, {
+                    let acc = Rc::clone(&acc);
+                    let runs = Rc::clone(&runs);
+                    move |_| {
+                        acc.set(memo());
+                        runs.set(runs.get() + 1);
+                    }
+                });
+                assert_eq!(acc.get(), 2497500);
+
+                writes[1].update(|n| *n += 1);
+                writes[10].update(|n| *n += 1);
+                writes[100].update(|n| *n += 1);
+
+                assert_eq!(acc.get(), 2497515);
+                assert_eq!(memo(), 2497515);
+                // the effect runs once per update, never on a half-updated diamond
+                assert_eq!(runs.get(), 4);
+            })
+            .dispose()
+        });
+    }
+
+    #[bench]
+    fn create_and_update_deep_diamond(b: &mut Bencher) {
+        use crate::{create_effect, create_memo, create_scope, create_signal};
+
+        b.iter(|| {
+            create_scope(|cx| {
+                let runs = Rc::new(Cell::new(0));
+                let (a, set_a) = create_signal(cx, 1);
+
+                // 100 layers of diamonds, each depending on the layer above
+                let mut top = create_memo(cx, move |_| a.get());
+                for _ in 0..100 {
+                    let left = create_memo(cx, move |_| top.get() + 1);
+                    let right = create_memo(cx, move |_| top.get() - 1);
+                    top = create_memo(cx, move |_| (left.get() + right.get()) / 2);
+                }
+                create_effect(cx, {
+                    let runs = Rc::clone(&runs);
+                    move |_| {
+                        assert_eq!(top.get(), a.get());
+                        runs.set(runs.get() + 1);
+                    }
+                });
+
+                for n in 2..12 {
+                    set_a(n);
+                }
+                assert_eq!(runs.get(), 11);
+            })
+            .dispose()
+        });
+    }
+
     #[bench]
     fn create_and_dispose_1000_scopes(b: &mut Bencher) {
         use crate::{create_effect, create_scope, create_signal};
@@ -205,6 +322,48 @@ mod tests {
         });
     }
 
+    #[bench]
+    fn sycamore_create_and_update_1000_diamonds(b: &mut Bencher) {
+        use sycamore::reactive::{create_effect, create_memo, create_scope, create_signal};
+
+        b.iter(|| {
+            let d = create_scope(|cx| {
+                let acc = Rc::new(Cell::new(0));
+                let sigs = Rc::new((0..1000).map(|n| create_signal(cx, n)).collect::<Vec<_>>());
+                let diamonds = Rc::new(
+                    sigs.iter()
+                        .map(|s| {
+                            let s = *s;
+                            let left = create_memo(cx, move || *s.get() * 2);
+                            let right = create_memo(cx, move || *s.get() * 3);
+                            create_memo(cx, move || *left.get() + *right.get())
+                        })
+                        .collect::<Vec<_>>(),
+                );
+                let memo = create_memo(cx, {
+                    let diamonds = Rc::clone(&diamonds);
+                    move || diamonds.iter().map(|d| *d.get()).sum::<i32>()
+                });
+                assert_eq!(*memo.get(), 2497500);
+                create_effect(cx, {
+                    let acc = Rc::clone(&acc);
+                    move || {
+                        acc.set(*memo.get());
+                    }
+                });
+                assert_eq!(acc.get(), 2497500);
+
+                sigs[1].set(*sigs[1].get() + 1);
+                sigs[10].set(*sigs[10].get() + 1);
+                sigs[100].set(*sigs[100].get() + 1);
+
+                assert_eq!(acc.get(), 2497515);
+                assert_eq!(*memo.get(), 2497515);
+            });
+            unsafe { d.dispose() };
+        });
+    }
+
     #[bench]
     fn sycamore_create_and_dispose_1000_scopes(b: &mut Bencher) {
         use sycamore::reactive::{create_effect, create_scope, create_signal};
@@ -1,5 +1,8 @@
-use crate::{create_isomorphic_effect, create_signal, ReadSignal, Scope};
-use std::fmt::Debug;
+use crate::{
+    untrack, AnyEffect, EffectId, ReadSignal, Runtime, Scope, ScopeProperty, SignalError, SignalId,
+    ThreadSafe,
+};
+use std::{fmt::Debug, marker::PhantomData};
 
 /// Creates an efficient derived reactive value based on other reactive values.
 ///
@@ -17,6 +20,14 @@ use std::fmt::Debug;
 /// As with [create_effect](crate::create_effect), the argument to the memo function is the previous value,
 /// i.e., the current value of the memo, which will be `None` for the initial calculation.
 ///
+/// Memos are lazy: the calculation doesn’t run until the memo is first read, and after
+/// one of its sources changes, it only runs again once something reads the memo. A memo
+/// that nothing reads (for example, because the part of the UI that uses it is hidden)
+/// costs nothing to keep around.
+///
+/// Like an effect, a memo owns whatever its calculation creates in the memo’s scope,
+/// which is disposed of before it runs again. See [create_effect](crate::create_effect).
+///
 /// ```
 /// # use leptos_reactive::*;
 /// # fn really_expensive_computation(value: i32) -> i32 { value };
@@ -41,11 +52,12 @@ use std::fmt::Debug;
 /// });
 ///
 /// // instead, we create a memo
-/// // 🆗 run #1: the calculation runs once immediately
+/// // 🆗 the calculation doesn't run until the memo is read
 /// let memoized = create_memo(cx, move |_| really_expensive_computation(value()));
 /// create_effect(cx, move |_| {
-///  // 🆗 reads the current value of the memo
-///   log::debug!("memoized = {}", memoized());
+///   // 🆗 run #1: reading the memo runs the calculation once
+///   let value = memoized();
+///   // do something with the value...
 /// });
 /// create_effect(cx, move |_| {
 ///   // ✅ reads the current value **without re-running the calculation**
@@ -54,11 +66,17 @@ use std::fmt::Debug;
 /// });
 /// # }).dispose();
 /// ```
-pub fn create_memo<T>(cx: Scope, f: impl FnMut(Option<T>) -> T + 'static) -> Memo<T>
+#[track_caller]
+pub fn create_memo<T>(cx: Scope, f: impl FnMut(Option<T>) -> T + ThreadSafe + 'static) -> Memo<T>
 where
-    T: PartialEq + Clone + Debug + 'static,
+    T: PartialEq + Clone + Debug + ThreadSafe + 'static,
 {
-    cx.runtime.create_memo(f)
+    let (memo, effect) = cx.runtime.create_memo(cx.id, f);
+    cx.with_scope_property(|prop| {
+        prop.push(ScopeProperty::Signal(memo.0.id));
+        prop.push(ScopeProperty::Effect(effect));
+    });
+    memo
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -89,10 +107,40 @@ where
     }
 
     pub fn with<U>(&self, f: impl Fn(&T) -> U) -> U {
-        // okay to unwrap here, because the value will *always* have initially
-        // been set by the effect, synchronously
+        // okay to unwrap here, because reading the signal runs the calculation
+        // if the memo is stale, so it will *always* have a value by now
         self.0.with(|n| f(n.as_ref().unwrap()))
     }
+
+    /// Applies a function to the current value of the memo, without subscribing the
+    /// running effect to it. See [untrack](crate::untrack).
+    pub fn with_untracked<U>(&self, f: impl Fn(&T) -> U) -> U {
+        untrack(|| self.with(f))
+    }
+
+    /// Clones and returns the current value of the memo, without subscribing the
+    /// running effect to it. See [untrack](crate::untrack).
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with_untracked(T::clone)
+    }
+
+    /// Like [Memo::with], but returns an error rather than panicking if the memo
+    /// has been disposed.
+    pub fn try_with<U>(&self, f: impl Fn(&T) -> U) -> Result<U, SignalError> {
+        self.0.try_with(|n| f(n.as_ref().unwrap()))
+    }
+
+    /// Like [Memo::get], but returns an error rather than panicking if the memo
+    /// has been disposed.
+    pub fn try_get(&self) -> Result<T, SignalError>
+    where
+        T: Clone,
+    {
+        self.try_with(T::clone)
+    }
 }
 
 impl<T> FnOnce<()> for Memo<T>
@@ -123,3 +171,52 @@ where
         self.get()
     }
 }
+
+/// The computation behind a [Memo]. It runs like an effect, but stores its value in
+/// a signal and only notifies that signal’s subscribers if the value has changed.
+pub(crate) struct MemoState<T, F>
+where
+    T: 'static,
+    F: FnMut(Option<T>) -> T,
+{
+    pub(crate) f: F,
+    pub(crate) signal: SignalId,
+    pub(crate) ty: PhantomData<T>,
+}
+
+impl<T, F> AnyEffect for MemoState<T, F>
+where
+    T: PartialEq + Clone + ThreadSafe + 'static,
+    F: FnMut(Option<T>) -> T + ThreadSafe,
+{
+    fn run(&mut self, id: EffectId, runtime: &Runtime) -> bool {
+        // clear previous dependencies
+        id.cleanup(runtime);
+
+        let value = {
+            let signals = runtime.signals.borrow();
+            signals.get(self.signal).cloned()
+        };
+        let value = match value {
+            Some(value) => value,
+            // the memo has been disposed
+            None => return false,
+        };
+        let prev = value
+            .borrow()
+            .downcast_ref::<Option<T>>()
+            .expect("memo signal should hold Option<T>")
+            .clone();
+
+        // recompute, with the memo as the current observer
+        let new_value = runtime.with_observer(Some(id), || (self.f)(prev.clone()));
+
+        let changed = prev.as_ref() != Some(&new_value);
+        if changed {
+            if let Some(value) = value.borrow_mut().downcast_mut::<Option<T>>() {
+                *value = Some(new_value);
+            }
+        }
+        changed
+    }
+}
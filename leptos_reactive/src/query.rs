@@ -0,0 +1,470 @@
+use std::{
+    any::TypeId, collections::HashMap, fmt::Debug, future::Future, hash::Hash, time::Duration,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    create_isomorphic_effect, create_memo, create_resource, create_signal, now, on_cleanup,
+    provide_context,
+    sync::{AnyValue, Cell, RefCell, Shared},
+    use_context, MaybeSend, PinnedFuture, ReadSignal, Resource, Scope, ScopeDisposer, ThreadSafe,
+};
+
+/// Configures how long a [QueryClient] treats cached values as fresh, and how long
+/// it keeps them once nothing is using them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct QueryOptions {
+    /// How long after it was fetched a value is fresh. A new query for a stale value
+    /// shows the cached value immediately, but refetches it in the background.
+    pub stale_time: Duration,
+    /// How long a value stays in the cache after the last query using it is disposed.
+    /// Expired values are only removed when [QueryClient::collect_garbage] runs: the
+    /// client doesn't schedule it on a timer.
+    pub gc_time: Duration,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            stale_time: Duration::ZERO,
+            gc_time: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// Provides a [QueryClient] to the given [Scope] and its descendants, so that
+/// [create_query] can share values between queries with the same key.
+///
+/// The client stores its cached values in this scope: they are disposed along with it.
+pub fn provide_query_client(cx: Scope, options: QueryOptions) -> QueryClient {
+    let client = QueryClient {
+        inner: Shared::new(QueryClientInner {
+            scope: cx,
+            options,
+            caches: Default::default(),
+        }),
+    };
+    provide_context(cx, client.clone());
+    client
+}
+
+/// Creates a [Query], which is like a [Resource](crate::Resource) whose value is
+/// cached by the [QueryClient] provided in a parent scope (see [provide_query_client]).
+///
+/// All the queries with the same key share a single value: the `fetcher` only runs for
+/// the first of them, and any that are created while it is loading wait for the same
+/// [Future]. A query whose key has a cached value that is older than the client’s
+/// [QueryOptions::stale_time] shows that value right away, while it is refetched in the
+/// background. Because each cached value is held by a resource, it is serialized during
+/// server rendering and hydrated in the browser like any other resource.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::time::Duration;
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// # create_scope(|cx| {
+/// let client = provide_query_client(cx, QueryOptions {
+///   stale_time: Duration::from_secs(60),
+///   ..Default::default()
+/// });
+///
+/// static FETCHES: AtomicUsize = AtomicUsize::new(0);
+/// async fn fetch_user(id: u32) -> String {
+///   FETCHES.fetch_add(1, Ordering::Relaxed);
+///   format!("user {id}")
+/// }
+///
+/// let (id, set_id) = create_signal(cx, 1);
+/// let user = create_query(cx, id, fetch_user);
+/// assert_eq!(user(), Some("user 1".to_string()));
+///
+/// // a second query for the same key reads the cached value, without fetching it again
+/// let same_user = create_query(cx, || 1, fetch_user);
+/// assert_eq!(same_user(), Some("user 1".to_string()));
+/// assert_eq!(FETCHES.load(Ordering::Relaxed), 1);
+///
+/// // like a resource, the query loads a new value when its key changes
+/// set_id(2);
+/// assert_eq!(user(), Some("user 2".to_string()));
+/// assert_eq!(FETCHES.load(Ordering::Relaxed), 2);
+///
+/// // invalidating a key refetches it for every query that is using it
+/// client.invalidate::<u32, String>(&1);
+/// assert_eq!(FETCHES.load(Ordering::Relaxed), 3);
+/// # }).dispose();
+/// ```
+pub fn create_query<K, V, Fu>(
+    cx: Scope,
+    key: impl Fn() -> K + ThreadSafe + 'static,
+    fetcher: impl Fn(K) -> Fu + ThreadSafe + 'static,
+) -> Query<K, V>
+where
+    K: Hash + Eq + Debug + Clone + ThreadSafe + 'static,
+    V: Debug + Clone + Serialize + DeserializeOwned + ThreadSafe + 'static,
+    Fu: Future<Output = V> + MaybeSend + 'static,
+{
+    let client = use_context::<QueryClient>(cx)
+        .expect("create_query requires a QueryClient: call provide_query_client in a parent scope");
+    let fetcher: QueryFetcher<K, V> = Shared::new(move |k| Box::pin(fetcher(k)) as PinnedFuture<V>);
+    let key = create_memo(cx, move |_| key());
+    let (resource, set_resource) = create_signal(cx, None);
+
+    // the key this query is currently observing, released when it changes or is disposed
+    let observed: Shared<RefCell<Option<K>>> = Default::default();
+
+    create_isomorphic_effect(cx, {
+        let client = client.clone();
+        let observed = Shared::clone(&observed);
+        move |_| {
+            let key = key.get();
+            let entry = cx.untrack(|| client.observe(&key, &fetcher));
+            set_resource(Some(entry));
+            let prev = observed.replace(Some(key));
+            if let Some(prev) = prev {
+                client.release::<K, V>(&prev);
+            }
+        }
+    });
+
+    on_cleanup(cx, move || {
+        let key = observed.borrow_mut().take();
+        if let Some(key) = key {
+            client.release::<K, V>(&key);
+        }
+    });
+
+    Query { cx, resource }
+}
+
+/// A cache of values loaded by [create_query], shared by all the queries in the [Scope]
+/// it was provided to by [provide_query_client].
+#[derive(Clone)]
+pub struct QueryClient {
+    inner: Shared<QueryClientInner>,
+}
+
+struct QueryClientInner {
+    scope: Scope,
+    options: QueryOptions,
+    caches: RefCell<HashMap<TypeId, Shared<dyn AnyQueryCache>>>,
+}
+
+impl Debug for QueryClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryClient")
+            .field("scope", &self.inner.scope)
+            .field("options", &self.inner.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl QueryClient {
+    /// The options this client was created with.
+    pub fn options(&self) -> QueryOptions {
+        self.inner.options
+    }
+
+    /// Refetches the value of type `V` cached for `key`, updating every query that uses it.
+    pub fn invalidate<K, V>(&self, key: &K)
+    where
+        K: Hash + Eq + Debug + Clone + ThreadSafe + 'static,
+        V: Debug + Clone + ThreadSafe + 'static,
+    {
+        let resource = self
+            .cache::<K, V>()
+            .entries
+            .borrow()
+            .get(key)
+            .map(|entry| entry.resource);
+        if let Some(resource) = resource {
+            resource.refetch();
+        }
+    }
+
+    /// Removes every cached value that no query has used for longer than
+    /// [QueryOptions::gc_time].
+    ///
+    /// This runs whenever a query stops using a value, but never on a timer, because the
+    /// reactive system has no way to schedule work for later. A value that expires while
+    /// no queries are being disposed stays cached until this is called again, so an app
+    /// that wants unused values freed on time should call it periodically, for example
+    /// with `set_interval` from `leptos_dom`.
+    pub fn collect_garbage(&self) {
+        let now = now();
+        for cache in self.caches() {
+            cache.collect_garbage(now, self.inner.options.gc_time);
+        }
+    }
+
+    fn caches(&self) -> Vec<Shared<dyn AnyQueryCache>> {
+        self.inner.caches.borrow().values().cloned().collect()
+    }
+
+    fn cache<K, V>(&self) -> Shared<QueryCache<K, V>>
+    where
+        K: Hash + Eq + Debug + Clone + ThreadSafe + 'static,
+        V: Debug + Clone + ThreadSafe + 'static,
+    {
+        let cache = Shared::clone(
+            self.inner
+                .caches
+                .borrow_mut()
+                .entry(TypeId::of::<QueryCache<K, V>>())
+                .or_insert_with(|| Shared::new(QueryCache::<K, V>::default())),
+        );
+        cache
+            .into_any()
+            .downcast()
+            .unwrap_or_else(|_| unreachable!("query caches are stored by their type"))
+    }
+
+    /// Starts using the cached value for `key`, loading it if it isn’t cached yet
+    /// and refetching it if it is stale.
+    fn observe<K, V>(&self, key: &K, fetcher: &QueryFetcher<K, V>) -> Resource<K, V>
+    where
+        K: Hash + Eq + Debug + Clone + ThreadSafe + 'static,
+        V: Debug + Clone + Serialize + DeserializeOwned + ThreadSafe + 'static,
+    {
+        let cache = self.cache::<K, V>();
+        let cached = cache.entries.borrow_mut().get_mut(key).map(|entry| {
+            entry.observers += 1;
+            entry.unused_since = None;
+            (entry.resource, entry.fetched_at.get())
+        });
+
+        match cached {
+            Some((resource, fetched_at)) => {
+                // a query that is already loading is shared, not fetched again
+                let stale = now().saturating_sub(fetched_at) >= self.inner.options.stale_time;
+                if stale && !resource.loading() {
+                    resource.refetch();
+                }
+                resource
+            }
+            None => {
+                let entry = self.create_entry(key, fetcher);
+                let resource = entry.resource;
+                cache.entries.borrow_mut().insert(key.clone(), entry);
+                resource
+            }
+        }
+    }
+
+    /// Stops using the cached value for `key`, so that it can be garbage-collected.
+    fn release<K, V>(&self, key: &K)
+    where
+        K: Hash + Eq + Debug + Clone + ThreadSafe + 'static,
+        V: Debug + Clone + ThreadSafe + 'static,
+    {
+        let now = now();
+        if let Some(entry) = self.cache::<K, V>().entries.borrow_mut().get_mut(key) {
+            entry.observers = entry.observers.saturating_sub(1);
+            if entry.observers == 0 {
+                entry.unused_since = Some(now);
+            }
+        }
+        self.collect_garbage();
+    }
+
+    fn create_entry<K, V>(&self, key: &K, fetcher: &QueryFetcher<K, V>) -> QueryEntry<K, V>
+    where
+        K: Hash + Eq + Debug + Clone + ThreadSafe + 'static,
+        V: Debug + Clone + Serialize + DeserializeOwned + ThreadSafe + 'static,
+    {
+        let fetched_at = Shared::new(Cell::new(now()));
+
+        // each value gets its own scope, so it can be disposed when it’s garbage-collected
+        let scope = self.inner.scope;
+        let (resource, disposer) = scope.runtime.run_scope_undisposed(
+            |cx| {
+                let key = key.clone();
+                let fetcher = Shared::clone(fetcher);
+                let fetched_at = Shared::clone(&fetched_at);
+                create_resource(
+                    cx,
+                    move || key.clone(),
+                    move |key| {
+                        let fut = fetcher(key);
+                        let fetched_at = Shared::clone(&fetched_at);
+                        async move {
+                            let value = fut.await;
+                            fetched_at.set(now());
+                            value
+                        }
+                    },
+                )
+            },
+            Some(scope),
+        );
+
+        QueryEntry {
+            resource,
+            fetched_at,
+            observers: 1,
+            unused_since: None,
+            disposer: Some(disposer),
+        }
+    }
+}
+
+/// A signal that reflects the cached value of an asynchronous query. See [create_query].
+#[derive(Debug)]
+pub struct Query<K, V>
+where
+    K: Debug + Clone + 'static,
+    V: Debug + Clone + 'static,
+{
+    cx: Scope,
+    resource: ReadSignal<Option<Resource<K, V>>>,
+}
+
+impl<K, V> Query<K, V>
+where
+    K: Debug + Clone + ThreadSafe + 'static,
+    V: Debug + Clone + ThreadSafe + 'static,
+{
+    pub fn read(&self) -> Option<V> {
+        self.resource
+            .get()
+            .and_then(|resource| resource.read_in(self.cx))
+    }
+
+    pub fn loading(&self) -> bool {
+        self.resource
+            .get()
+            .map(|resource| resource.loading())
+            .unwrap_or(false)
+    }
+
+    /// Refetches the value for the current key, updating every query that uses it.
+    pub fn refetch(&self) {
+        if let Some(resource) = self.resource.get() {
+            resource.refetch();
+        }
+    }
+}
+
+impl<K, V> Clone for Query<K, V>
+where
+    K: Debug + Clone + 'static,
+    V: Debug + Clone + 'static,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for Query<K, V>
+where
+    K: Debug + Clone + 'static,
+    V: Debug + Clone + 'static,
+{
+}
+
+impl<K, V> FnOnce<()> for Query<K, V>
+where
+    K: Debug + Clone + ThreadSafe + 'static,
+    V: Debug + Clone + ThreadSafe + 'static,
+{
+    type Output = Option<V>;
+
+    extern "rust-call" fn call_once(self, _args: ()) -> Self::Output {
+        self.read()
+    }
+}
+
+impl<K, V> FnMut<()> for Query<K, V>
+where
+    K: Debug + Clone + ThreadSafe + 'static,
+    V: Debug + Clone + ThreadSafe + 'static,
+{
+    extern "rust-call" fn call_mut(&mut self, _args: ()) -> Self::Output {
+        self.read()
+    }
+}
+
+impl<K, V> Fn<()> for Query<K, V>
+where
+    K: Debug + Clone + ThreadSafe + 'static,
+    V: Debug + Clone + ThreadSafe + 'static,
+{
+    extern "rust-call" fn call(&self, _args: ()) -> Self::Output {
+        self.read()
+    }
+}
+
+#[cfg(not(feature = "multithreaded"))]
+type QueryFetcher<K, V> = Shared<dyn Fn(K) -> PinnedFuture<V>>;
+
+#[cfg(feature = "multithreaded")]
+type QueryFetcher<K, V> = Shared<dyn Fn(K) -> PinnedFuture<V> + Send + Sync>;
+
+/// The cached values for one type of key and value.
+struct QueryCache<K, V>
+where
+    K: Debug + Clone + 'static,
+    V: Debug + Clone + 'static,
+{
+    entries: RefCell<HashMap<K, QueryEntry<K, V>>>,
+}
+
+impl<K, V> Default for QueryCache<K, V>
+where
+    K: Debug + Clone + 'static,
+    V: Debug + Clone + 'static,
+{
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+        }
+    }
+}
+
+struct QueryEntry<K, V>
+where
+    K: Debug + Clone + 'static,
+    V: Debug + Clone + 'static,
+{
+    resource: Resource<K, V>,
+    fetched_at: Shared<Cell<Duration>>,
+    /// The number of queries using this value.
+    observers: usize,
+    unused_since: Option<Duration>,
+    disposer: Option<ScopeDisposer>,
+}
+
+trait AnyQueryCache: ThreadSafe {
+    fn into_any(self: Shared<Self>) -> Shared<AnyValue>;
+
+    fn collect_garbage(&self, now: Duration, gc_time: Duration);
+}
+
+impl<K, V> AnyQueryCache for QueryCache<K, V>
+where
+    K: Hash + Eq + Debug + Clone + ThreadSafe + 'static,
+    V: Debug + Clone + ThreadSafe + 'static,
+{
+    fn into_any(self: Shared<Self>) -> Shared<AnyValue> {
+        self
+    }
+
+    fn collect_garbage(&self, now: Duration, gc_time: Duration) {
+        let mut disposers = Vec::new();
+        self.entries.borrow_mut().retain(|_, entry| {
+            let expired = entry
+                .unused_since
+                .map(|since| now.saturating_sub(since) >= gc_time)
+                .unwrap_or(false);
+            if expired {
+                disposers.extend(entry.disposer.take());
+            }
+            !expired
+        });
+
+        // disposing runs cleanups, so it shouldn’t happen while the cache is borrowed
+        for disposer in disposers {
+            disposer.dispose();
+        }
+    }
+}
@@ -1,20 +1,15 @@
-use std::{
-    any::Any,
-    cell::{Cell, RefCell},
-    collections::HashSet,
-    fmt::Debug,
-    future::Future,
-    marker::PhantomData,
-    pin::Pin,
-    rc::Rc,
-};
+use futures::future::{abortable, AbortHandle};
+use std::{any::Any, collections::HashSet, fmt::Debug, future::Future, marker::PhantomData};
 
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    create_isomorphic_effect, create_memo, create_signal, queue_microtask, runtime::Runtime,
-    spawn::spawn_local, use_context, Memo, ReadSignal, Scope, ScopeProperty, SuspenseContext,
-    WriteSignal,
+    create_isomorphic_effect, create_memo, create_signal, on_cleanup,
+    runtime::{try_with_runtime, with_runtime, RuntimeId},
+    spawn::{queue_runtime_microtask, spawn_task},
+    sync::{Cell, RefCell, Shared},
+    use_context, DefaultSerializer, MaybeSend, Memo, PinnedFuture, ReadSignal, ResourceSerializer,
+    Scope, ScopeProperty, SignalError, SuspenseContext, ThreadSafe, WriteSignal,
 };
 
 /// Creates [Resource](crate::Resource), which is a signal that reflects the
@@ -50,15 +45,44 @@ use crate::{
 /// assert_eq!(cats(), Some(vec!["2".to_string()]));
 /// # }).dispose();
 /// ```
+#[track_caller]
 pub fn create_resource<S, T, Fu>(
     cx: Scope,
-    source: impl Fn() -> S + 'static,
-    fetcher: impl Fn(S) -> Fu + 'static,
+    source: impl Fn() -> S + ThreadSafe + 'static,
+    fetcher: impl Fn(S) -> Fu + ThreadSafe + 'static,
 ) -> Resource<S, T>
 where
-    S: PartialEq + Debug + Clone + 'static,
-    T: Debug + Clone + Serialize + DeserializeOwned + 'static,
-    Fu: Future<Output = T> + 'static,
+    S: PartialEq + Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + Serialize + DeserializeOwned + ThreadSafe + 'static,
+    Fu: Future<Output = T> + MaybeSend + 'static,
+{
+    create_resource_with_serializer(cx, source, fetcher, DefaultSerializer::default())
+}
+
+/// Creates a [Resource](crate::Resource) whose value is sent from the server to the
+/// client in the format of the given [ResourceSerializer], rather than the
+/// [DefaultSerializer].
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// // with the `bincode` feature, `Bincode` is much smaller for large numeric data
+/// let samples = create_resource_with_serializer(cx, || (), |_| async { vec![0.5; 4] }, Json);
+/// assert_eq!(samples(), Some(vec![0.5; 4]));
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_resource_with_serializer<S, T, Fu, Ser>(
+    cx: Scope,
+    source: impl Fn() -> S + ThreadSafe + 'static,
+    fetcher: impl Fn(S) -> Fu + ThreadSafe + 'static,
+    serializer: Ser,
+) -> Resource<S, T>
+where
+    S: PartialEq + Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
+    Fu: Future<Output = T> + MaybeSend + 'static,
+    Ser: ResourceSerializer<T>,
 {
     #[cfg(not(feature = "ssr"))]
     let initial_value = None;
@@ -70,21 +94,124 @@ where
         initial_fut.now_or_never()
     };
 
-    create_resource_with_initial_value(cx, source, fetcher, initial_value)
+    create_serialized_resource(cx, source, fetcher, initial_value, serializer)
 }
 
 /// Creates a [Resource](crate::Resource) with the given initial value, which
 /// will only generate and run a [Future] using the `fetcher` when the `source` changes.
+#[track_caller]
 pub fn create_resource_with_initial_value<S, T, Fu>(
     cx: Scope,
-    source: impl Fn() -> S + 'static,
-    fetcher: impl Fn(S) -> Fu + 'static,
+    source: impl Fn() -> S + ThreadSafe + 'static,
+    fetcher: impl Fn(S) -> Fu + ThreadSafe + 'static,
+    initial_value: Option<T>,
+) -> Resource<S, T>
+where
+    S: PartialEq + Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + Serialize + DeserializeOwned + ThreadSafe + 'static,
+    Fu: Future<Output = T> + MaybeSend + 'static,
+{
+    create_serialized_resource(
+        cx,
+        source,
+        fetcher,
+        initial_value,
+        DefaultSerializer::default(),
+    )
+}
+
+#[track_caller]
+fn create_serialized_resource<S, T, Fu, Ser>(
+    cx: Scope,
+    source: impl Fn() -> S + ThreadSafe + 'static,
+    fetcher: impl Fn(S) -> Fu + ThreadSafe + 'static,
     initial_value: Option<T>,
+    serializer: Ser,
+) -> Resource<S, T>
+where
+    S: PartialEq + Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
+    Fu: Future<Output = T> + MaybeSend + 'static,
+    Ser: ResourceSerializer<T>,
+{
+    let r = create_resource_state(cx, source, fetcher, initial_value, false);
+
+    let id = with_runtime(cx.runtime, |runtime| {
+        runtime.create_resource(Shared::new(SerializedResourceState {
+            state: (*r).clone(),
+            serializer,
+        }))
+    });
+
+    create_isomorphic_effect(cx, {
+        let r = Shared::clone(&r);
+        move |_| {
+            load_resource(cx, id, r.clone(), serializer);
+        }
+    });
+
+    register_resource(cx, id, r)
+}
+
+/// Creates a [Resource](crate::Resource) that is only loaded in the browser.
+///
+/// Unlike [create_resource], its value is never serialized to be sent from the server
+/// to the client, so it can hold values that can’t be serialized, like DOM handles or
+/// [Rc](std::rc::Rc)s. On the server, its `fetcher` never runs and its value is always
+/// `None`; it doesn’t hold up any `<Suspense/>` that reads it there. In the browser,
+/// `<Suspense/>` waits for it like any other resource.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// // a value that can't be serialized
+/// #[derive(Debug, Clone)]
+/// struct Handle(String);
+///
+/// let (name, _) = create_signal(cx, "canvas".to_string());
+/// let handle = create_local_resource(cx, name, |name| async move { Handle(name) });
+///
+/// // in the browser, it loads like any other resource
+/// assert_eq!(handle.read().map(|handle| handle.0.to_string()), Some("canvas".to_string()));
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_local_resource<S, T, Fu>(
+    cx: Scope,
+    source: impl Fn() -> S + ThreadSafe + 'static,
+    fetcher: impl Fn(S) -> Fu + ThreadSafe + 'static,
 ) -> Resource<S, T>
 where
-    S: PartialEq + Debug + Clone + 'static,
-    T: Debug + Clone + Serialize + DeserializeOwned + 'static,
-    Fu: Future<Output = T> + 'static,
+    S: PartialEq + Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
+    Fu: Future<Output = T> + MaybeSend + 'static,
+{
+    let r = create_resource_state(cx, source, fetcher, None, true);
+
+    let id = with_runtime(cx.runtime, |runtime| {
+        runtime.create_resource(Shared::new(LocalResourceState((*r).clone())))
+    });
+
+    #[cfg(not(feature = "ssr"))]
+    create_isomorphic_effect(cx, {
+        let r = Shared::clone(&r);
+        move |_| r.load(false)
+    });
+
+    register_resource(cx, id, r)
+}
+
+fn create_resource_state<S, T, Fu>(
+    cx: Scope,
+    source: impl Fn() -> S + ThreadSafe + 'static,
+    fetcher: impl Fn(S) -> Fu + ThreadSafe + 'static,
+    initial_value: Option<T>,
+    local: bool,
+) -> Shared<ResourceState<S, T>>
+where
+    S: PartialEq + Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
+    Fu: Future<Output = T> + MaybeSend + 'static,
 {
     let resolved = initial_value.is_some();
     let (value, set_value) = create_signal(cx, initial_value);
@@ -92,10 +219,10 @@ where
     let (loading, set_loading) = create_signal(cx, false);
 
     let (track, trigger) = create_signal(cx, 0);
-    let fetcher = Rc::new(move |s| Box::pin(fetcher(s)) as Pin<Box<dyn Future<Output = T>>>);
+    let fetcher: Fetcher<S, T> = Shared::new(move |s| Box::pin(fetcher(s)) as PinnedFuture<T>);
     let source = create_memo(cx, move |_| source());
 
-    let r = Rc::new(ResourceState {
+    Shared::new(ResourceState {
         scope: cx,
         value,
         set_value,
@@ -105,22 +232,30 @@ where
         trigger,
         source,
         fetcher,
-        resolved: Rc::new(Cell::new(resolved)),
-        scheduled: Rc::new(Cell::new(false)),
+        resolved: Shared::new(Cell::new(resolved)),
+        scheduled: Shared::new(Cell::new(false)),
         suspense_contexts: Default::default(),
-    });
-
-    let id = cx.runtime.create_resource(Rc::clone(&r));
-
-    create_isomorphic_effect(cx, {
-        let r = Rc::clone(&r);
-        move |_| {
-            load_resource(cx, id, r.clone());
-        }
-    });
+        version: Default::default(),
+        pending_load: Default::default(),
+        local,
+    })
+}
 
+#[track_caller]
+fn register_resource<S, T>(
+    cx: Scope,
+    id: ResourceId,
+    r: Shared<ResourceState<S, T>>,
+) -> Resource<S, T>
+where
+    S: Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
+{
     cx.with_scope_property(|prop| prop.push(ScopeProperty::Resource(id)));
 
+    // stop polling the current load, if any, once the resource has been disposed
+    on_cleanup(cx, move || r.abort());
+
     Resource {
         runtime: cx.runtime,
         id,
@@ -130,107 +265,169 @@ where
 }
 
 #[cfg(not(feature = "hydrate"))]
-fn load_resource<S, T>(_cx: Scope, _id: ResourceId, r: Rc<ResourceState<S, T>>)
-where
-    S: PartialEq + Debug + Clone + 'static,
-    T: Debug + Clone + Serialize + DeserializeOwned + 'static,
+fn load_resource<S, T, Ser>(
+    _cx: Scope,
+    _id: ResourceId,
+    r: Shared<ResourceState<S, T>>,
+    _serializer: Ser,
+) where
+    S: PartialEq + Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
+    Ser: ResourceSerializer<T>,
 {
     r.load(false)
 }
 
 #[cfg(feature = "hydrate")]
-fn load_resource<S, T>(cx: Scope, id: ResourceId, r: Rc<ResourceState<S, T>>)
-where
-    S: PartialEq + Debug + Clone + 'static,
-    T: Debug + Clone + Serialize + DeserializeOwned + 'static,
+fn load_resource<S, T, Ser>(
+    cx: Scope,
+    id: ResourceId,
+    r: Shared<ResourceState<S, T>>,
+    serializer: Ser,
+) where
+    S: PartialEq + Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
+    Ser: ResourceSerializer<T>,
 {
     use wasm_bindgen::{JsCast, UnwrapThrowExt};
 
-    if let Some(ref mut context) = *cx.runtime.shared_context.borrow_mut() {
-        if let Some(data) = context.resolved_resources.remove(&id) {
-            context.pending_resources.remove(&id); // no longer pending
-            r.resolved.set(true);
-            //let decoded = base64::decode(&data).unwrap_throw();
-            //let res = bincode::deserialize(&decoded).unwrap_throw();
-            let res = serde_json::from_str(&data).unwrap_throw();
-            r.set_value.update(|n| *n = Some(res));
-            r.set_loading.update(|n| *n = false);
-
-            // for reactivity
-            _ = r.source.get();
-        } else if context.pending_resources.remove(&id) {
-            r.set_loading.update(|n| *n = true);
-            r.trigger.update(|n| *n += 1);
-
-            let resolve = {
-                let runtime = cx.runtime;
-                let resolved = r.resolved.clone();
-                let set_value = r.set_value;
-                let set_loading = r.set_loading;
-                move |res: String| {
-                    //let decoded = base64::decode(&res).unwrap_throw();
-                    //let res = bincode::deserialize(&decoded).unwrap_throw();
-                    let res = serde_json::from_str(&res).unwrap_throw();
-                    resolved.set(true);
-                    set_value.update(|n| *n = res);
-                    set_loading.update(|n| *n = false);
-                }
-            };
-            let resolve =
-                wasm_bindgen::closure::Closure::wrap(Box::new(resolve) as Box<dyn Fn(String)>);
-            let resource_resolvers = js_sys::Reflect::get(
-                &web_sys::window().unwrap(),
-                &wasm_bindgen::JsValue::from_str("__LEPTOS_RESOURCE_RESOLVERS"),
-            )
-            .unwrap();
-            let id = serde_json::to_string(&id).unwrap();
-            js_sys::Reflect::set(
-                &resource_resolvers,
-                &wasm_bindgen::JsValue::from_str(&id),
-                resolve.as_ref().unchecked_ref(),
-            );
-
-            // for reactivity
-            _ = r.source.get();
+    with_runtime(cx.runtime, |runtime| {
+        if let Some(ref mut context) = *runtime.shared_context.borrow_mut() {
+            if let Some(data) = context.resolved_resources.remove(&id) {
+                context.pending_resources.remove(&id); // no longer pending
+                r.resolved.set(true);
+                let res = serializer.deserialize(&data).unwrap_throw();
+                r.set_value.update(|n| *n = Some(res));
+                r.set_loading.update(|n| *n = false);
+
+                // for reactivity
+                _ = r.source.get();
+            } else if context.pending_resources.remove(&id) {
+                r.set_loading.update(|n| *n = true);
+                r.trigger.update(|n| *n += 1);
+
+                let resolve = {
+                    let runtime = cx.runtime;
+                    let resolved = r.resolved.clone();
+                    let set_value = r.set_value;
+                    let set_loading = r.set_loading;
+                    move |res: String| {
+                        let res = serializer.deserialize(&res).unwrap_throw();
+                        resolved.set(true);
+                        _ = set_value.try_update(|n| *n = Some(res));
+                        _ = set_loading.try_update(|n| *n = false);
+                    }
+                };
+                let resolve =
+                    wasm_bindgen::closure::Closure::wrap(Box::new(resolve) as Box<dyn Fn(String)>);
+                let resource_resolvers = js_sys::Reflect::get(
+                    &web_sys::window().unwrap(),
+                    &wasm_bindgen::JsValue::from_str("__LEPTOS_RESOURCE_RESOLVERS"),
+                )
+                .unwrap();
+                let id = serde_json::to_string(&id).unwrap();
+                js_sys::Reflect::set(
+                    &resource_resolvers,
+                    &wasm_bindgen::JsValue::from_str(&id),
+                    resolve.as_ref().unchecked_ref(),
+                );
+
+                // for reactivity
+                _ = r.source.get();
+            } else {
+                r.load(false);
+            }
         } else {
-            r.load(false);
+            r.load(false)
         }
-    } else {
-        r.load(false)
-    }
+    })
 }
 
 impl<S, T> Resource<S, T>
 where
-    S: Debug + Clone + 'static,
-    T: Debug + Clone + 'static,
+    S: Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
 {
     pub fn read(&self) -> Option<T> {
-        self.runtime
-            .resource(self.id, |resource: &ResourceState<S, T>| resource.read())
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.read(resource.scope)
+            })
+        })
+    }
+
+    /// Like [Resource::read], but returns an error rather than panicking if the resource
+    /// has been disposed.
+    pub fn try_read(&self) -> Result<Option<T>, SignalError> {
+        self.try_with(Option::clone)
+    }
+
+    /// Applies a function to the current value of the resource, like [Resource::read]
+    /// without cloning it, but returns an error rather than panicking if the resource
+    /// has been disposed.
+    pub fn try_with<U>(&self, f: impl FnOnce(&Option<T>) -> U) -> Result<U, SignalError> {
+        try_with_runtime(self.runtime, |runtime| {
+            runtime.try_resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.with(resource.scope, f)
+            })
+        })?
+    }
+
+    /// The same as [Resource::try_read], named like [ReadSignal::try_get].
+    pub fn try_get(&self) -> Result<Option<T>, SignalError> {
+        self.try_read()
+    }
+
+    /// Applies a function to the current value of the resource, without subscribing the
+    /// running effect to it or making any `<Suspense/>` wait for it to load.
+    pub fn with_untracked<U>(&self, f: impl FnOnce(&Option<T>) -> U) -> U {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.value.with_untracked(f)
+            })
+        })
+    }
+
+    /// Clones and returns the current value of the resource, without subscribing the
+    /// running effect to it or making any `<Suspense/>` wait for it to load.
+    pub fn read_untracked(&self) -> Option<T> {
+        self.with_untracked(Option::clone)
+    }
+
+    /// Reads the resource on behalf of `cx`, so that any `<Suspense/>` above `cx`, rather
+    /// than above the scope that created the resource, waits for it to load.
+    pub(crate) fn read_in(&self, cx: Scope) -> Option<T> {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| resource.read(cx))
+        })
     }
 
     pub fn loading(&self) -> bool {
-        self.runtime
-            .resource(self.id, |resource: &ResourceState<S, T>| {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
                 resource.loading.get()
             })
+        })
     }
 
     pub fn refetch(&self) {
-        self.runtime
-            .resource(self.id, |resource: &ResourceState<S, T>| resource.refetch())
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| resource.refetch())
+        })
     }
 
+    /// Resolves to the value of the resource, serialized by its [ResourceSerializer].
+    ///
+    /// # Panics
+    /// Panics if the resource has been disposed, or was created by [create_local_resource].
     #[cfg(feature = "ssr")]
-    pub async fn to_serialization_resolver(&self) -> (ResourceId, String)
-    where
-        T: Serialize + DeserializeOwned,
-    {
-        self.runtime
-            .resource(self.id, |resource: &ResourceState<S, T>| {
-                resource.to_serialization_resolver(self.id)
-            })
+    pub async fn to_serialization_resolver(&self) -> (ResourceId, String) {
+        let resolver = with_runtime(self.runtime, |runtime| {
+            let resource = runtime.resources.borrow().get(self.id).cloned();
+            resource.and_then(|resource| resource.to_serialization_resolver(self.id))
+        });
+        resolver
+            .unwrap_or_else(|| panic!("couldn't serialize {:?}", self.id))
             .await
     }
 }
@@ -241,7 +438,7 @@ where
     S: Debug + Clone + 'static,
     T: Debug + Clone + 'static,
 {
-    runtime: &'static Runtime,
+    pub(crate) runtime: RuntimeId,
     pub(crate) id: ResourceId,
     pub(crate) source_ty: PhantomData<S>,
     pub(crate) out_ty: PhantomData<T>,
@@ -274,8 +471,8 @@ where
 
 impl<S, T> FnOnce<()> for Resource<S, T>
 where
-    S: Debug + Clone + 'static,
-    T: Debug + Clone + 'static,
+    S: Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
 {
     type Output = Option<T>;
 
@@ -286,8 +483,8 @@ where
 
 impl<S, T> FnMut<()> for Resource<S, T>
 where
-    S: Debug + Clone + 'static,
-    T: Debug + Clone + 'static,
+    S: Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
 {
     extern "rust-call" fn call_mut(&mut self, _args: ()) -> Self::Output {
         self.read()
@@ -296,8 +493,8 @@ where
 
 impl<S, T> Fn<()> for Resource<S, T>
 where
-    S: Debug + Clone + 'static,
-    T: Debug + Clone + 'static,
+    S: Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
 {
     extern "rust-call" fn call(&self, _args: ()) -> Self::Output {
         self.read()
@@ -318,25 +515,97 @@ where
     track: ReadSignal<usize>,
     trigger: WriteSignal<usize>,
     source: Memo<S>,
-    #[allow(clippy::type_complexity)]
-    fetcher: Rc<dyn Fn(S) -> Pin<Box<dyn Future<Output = T>>>>,
-    resolved: Rc<Cell<bool>>,
-    scheduled: Rc<Cell<bool>>,
-    suspense_contexts: Rc<RefCell<HashSet<SuspenseContext>>>,
+    fetcher: Fetcher<S, T>,
+    resolved: Shared<Cell<bool>>,
+    scheduled: Shared<Cell<bool>>,
+    suspense_contexts: Shared<RefCell<HashSet<SuspenseContext>>>,
+    /// Incremented by each load, so that a load can tell whether it is still the latest.
+    version: Shared<Cell<usize>>,
+    pending_load: Shared<RefCell<Option<PendingLoad>>>,
+    /// Whether it was created by [create_local_resource], so is never loaded on the server.
+    local: bool,
 }
 
+/// The state of a resource, registered with the runtime along with the
+/// [ResourceSerializer] used to send its value from the server to the client.
+struct SerializedResourceState<S, T, Ser>
+where
+    S: 'static,
+    T: Clone + Debug + 'static,
+{
+    state: ResourceState<S, T>,
+    // values are only serialized on the server
+    #[cfg_attr(not(feature = "ssr"), allow(dead_code))]
+    serializer: Ser,
+}
+
+/// The state of a resource created by [create_local_resource], which is registered with
+/// the runtime without any way to serialize its value.
+struct LocalResourceState<S, T>(ResourceState<S, T>)
+where
+    S: 'static,
+    T: Clone + Debug + 'static;
+
+/// A load whose [Future] is still running.
+struct PendingLoad {
+    abort_handle: AbortHandle,
+    #[cfg(feature = "transition")]
+    transition: Option<crate::Transition>,
+}
+
+impl PendingLoad {
+    /// Stops polling the load’s [Future], and stops counting it as pending.
+    fn cancel(self, suspense_contexts: &RefCell<HashSet<SuspenseContext>>) {
+        self.abort_handle.abort();
+        self.resolve(suspense_contexts);
+    }
+
+    /// Stops counting the load as pending.
+    fn resolve(self, suspense_contexts: &RefCell<HashSet<SuspenseContext>>) {
+        for suspense_context in suspense_contexts.borrow().iter() {
+            #[cfg(feature = "transition")]
+            if self.transition.is_some() {
+                suspense_context.decrement_transition();
+                continue;
+            }
+            suspense_context.decrement();
+        }
+
+        #[cfg(feature = "transition")]
+        if let Some(transition) = self.transition {
+            transition.decrement();
+        }
+    }
+}
+
+#[cfg(not(feature = "multithreaded"))]
+type Fetcher<S, T> = Shared<dyn Fn(S) -> PinnedFuture<T>>;
+
+#[cfg(feature = "multithreaded")]
+type Fetcher<S, T> = Shared<dyn Fn(S) -> PinnedFuture<T> + Send + Sync>;
+
 impl<S, T> ResourceState<S, T>
 where
-    S: Debug + Clone + 'static,
-    T: Debug + Clone + 'static,
+    S: Debug + Clone + ThreadSafe + 'static,
+    T: Debug + Clone + ThreadSafe + 'static,
 {
-    pub fn read(&self) -> Option<T> {
-        let suspense_cx = use_context::<SuspenseContext>(self.scope);
+    pub fn read(&self, cx: Scope) -> Option<T> {
+        self.with(cx, Option::clone)
+    }
 
-        let v = self.value.get();
+    pub fn with<U>(&self, cx: Scope, f: impl FnOnce(&Option<T>) -> U) -> U {
+        let (v, has_value) = self.value.with(|v| (f(v), v.is_some()));
+
+        // a local resource never loads on the server, so it shouldn’t hold up `<Suspense/>`
+        if cfg!(feature = "ssr") && self.local {
+            return v;
+        }
+
+        let suspense_cx = use_context::<SuspenseContext>(cx);
 
         let suspense_contexts = self.suspense_contexts.clone();
-        let has_value = v.is_some();
+        #[cfg(feature = "transition")]
+        let pending_load = Shared::clone(&self.pending_load);
 
         let increment = move |_| {
             if let Some(s) = &suspense_cx {
@@ -348,13 +617,23 @@ where
                     // because the context has been tracked here
                     // on the first read, resource is already loading without having incremented
                     if !has_value {
+                        // the load that is already running may be part of a transition
+                        #[cfg(feature = "transition")]
+                        if let Some(PendingLoad {
+                            transition: Some(_),
+                            ..
+                        }) = &*pending_load.borrow()
+                        {
+                            s.increment_transition();
+                            return;
+                        }
                         s.increment();
                     }
                 }
             }
         };
 
-        create_isomorphic_effect(self.scope, increment);
+        create_isomorphic_effect(cx, increment);
 
         v
     }
@@ -375,8 +654,8 @@ where
 
         // `scheduled` is true for the rest of this code only
         self.scheduled.set(true);
-        queue_microtask({
-            let scheduled = Rc::clone(&self.scheduled);
+        queue_runtime_microtask(self.scope.runtime, {
+            let scheduled = Shared::clone(&self.scheduled);
             move || {
                 scheduled.set(false);
             }
@@ -385,75 +664,170 @@ where
         self.set_loading.update(|n| *n = true);
         self.trigger.update(|n| *n += 1);
 
+        // if this is loading as part of a transition, keep it pending until we resolve
+        #[cfg(feature = "transition")]
+        let transition = with_runtime(self.scope.runtime, |runtime| runtime.transition.get());
+        #[cfg(feature = "transition")]
+        if let Some(transition) = transition {
+            transition.increment();
+        }
+
         // increment counter everywhere it's read
         let suspense_contexts = self.suspense_contexts.clone();
 
         for suspense_context in suspense_contexts.borrow().iter() {
+            #[cfg(feature = "transition")]
+            if transition.is_some() {
+                suspense_context.increment_transition();
+                continue;
+            }
             suspense_context.increment();
         }
 
+        // a newer load makes the result of any earlier one stale, so cancel it
+        let version = self.version.get() + 1;
+        self.version.set(version);
+        let (fut, abort_handle) = abortable(fut);
+        let prev_load = self.pending_load.replace(Some(PendingLoad {
+            abort_handle,
+            #[cfg(feature = "transition")]
+            transition,
+        }));
+        if let Some(prev_load) = prev_load {
+            prev_load.cancel(&suspense_contexts);
+        }
+
         // run the Future
-        spawn_local({
+        spawn_task(self.scope.runtime, {
             let resolved = self.resolved.clone();
             let set_value = self.set_value;
             let set_loading = self.set_loading;
+            let latest_version = Shared::clone(&self.version);
+            let pending_load = Shared::clone(&self.pending_load);
             async move {
-                let res = fut.await;
+                // a cancelled load either stops being polled or, if it had already
+                // finished, is discarded here because a newer load has started
+                let res = match fut.await {
+                    Ok(res) if latest_version.get() == version => res,
+                    _ => return,
+                };
 
                 resolved.set(true);
 
-                set_value.update(|n| *n = Some(res));
-                set_loading.update(|n| *n = false);
+                // the resource may have been disposed while it was loading
+                _ = set_value.try_update(|n| *n = Some(res));
+                _ = set_loading.try_update(|n| *n = false);
 
-                for suspense_context in suspense_contexts.borrow().iter() {
-                    suspense_context.decrement();
+                let load = pending_load.borrow_mut().take();
+                if let Some(load) = load {
+                    load.resolve(&suspense_contexts);
                 }
             }
         })
     }
 
+    /// Cancels the current load, if there is one, without updating the resource. Any
+    /// `<Suspense/>` or [Transition](crate::Transition) waiting for it stops waiting.
+    fn abort(&self) {
+        let load = self.pending_load.borrow_mut().take();
+        if let Some(load) = load {
+            load.cancel(&self.suspense_contexts);
+        }
+    }
+
     #[cfg(feature = "ssr")]
     pub fn resource_to_serialization_resolver(
         &self,
         id: ResourceId,
-    ) -> std::pin::Pin<Box<dyn futures::Future<Output = (ResourceId, String)>>>
-    where
-        T: Serialize,
-    {
+        serializer: impl ResourceSerializer<T>,
+    ) -> PinnedFuture<(ResourceId, String)> {
         let fut = (self.fetcher)(self.source.get());
         Box::pin(async move {
             let res = fut.await;
-            (id, serde_json::to_string(&res).unwrap())
-            //(id, base64::encode(&bincode::serialize(&res).unwrap()))
+            (id, serializer.serialize(&res).unwrap())
         })
     }
 }
 
-pub(crate) trait AnyResource {
+pub(crate) trait AnyResource: ThreadSafe {
     fn as_any(&self) -> &dyn Any;
 
+    /// Whether it was created by [create_local_resource], so is never loaded on the server
+    /// or sent to the client.
+    fn is_local(&self) -> bool;
+
+    /// Resolves to its serialized value once it has loaded, or `None` for a local resource.
     #[cfg(feature = "ssr")]
     fn to_serialization_resolver(
         &self,
         id: ResourceId,
-    ) -> Pin<Box<dyn Future<Output = (ResourceId, String)>>>;
+    ) -> Option<PinnedFuture<(ResourceId, String)>>;
+
+    /// The signals holding its source, value, and loading state.
+    #[cfg(feature = "debug")]
+    fn graph_edges(&self) -> (crate::SignalId, crate::SignalId, crate::SignalId);
 }
 
-impl<S, T> AnyResource for ResourceState<S, T>
+impl<S, T, Ser> AnyResource for SerializedResourceState<S, T, Ser>
 where
-    S: Debug + Clone,
-    T: Clone + Debug + Serialize + DeserializeOwned,
+    S: Debug + Clone + ThreadSafe,
+    T: Clone + Debug + ThreadSafe,
+    Ser: ResourceSerializer<T>,
 {
+    // reads downcast to the inner state, whatever its serializer
     fn as_any(&self) -> &dyn Any {
-        self
+        &self.state
+    }
+
+    fn is_local(&self) -> bool {
+        self.state.local
     }
 
     #[cfg(feature = "ssr")]
     fn to_serialization_resolver(
         &self,
         id: ResourceId,
-    ) -> Pin<Box<dyn Future<Output = (ResourceId, String)>>> {
-        let fut = self.resource_to_serialization_resolver(id);
-        Box::pin(fut)
+    ) -> Option<PinnedFuture<(ResourceId, String)>> {
+        Some(
+            self.state
+                .resource_to_serialization_resolver(id, self.serializer),
+        )
+    }
+
+    #[cfg(feature = "debug")]
+    fn graph_edges(&self) -> (crate::SignalId, crate::SignalId, crate::SignalId) {
+        (
+            self.state.source.0.id,
+            self.state.value.id,
+            self.state.loading.id,
+        )
+    }
+}
+
+impl<S, T> AnyResource for LocalResourceState<S, T>
+where
+    S: Debug + Clone + ThreadSafe,
+    T: Clone + Debug + ThreadSafe,
+{
+    // reads downcast to the inner state, just as for other resources
+    fn as_any(&self) -> &dyn Any {
+        &self.0
+    }
+
+    fn is_local(&self) -> bool {
+        self.0.local
+    }
+
+    #[cfg(feature = "ssr")]
+    fn to_serialization_resolver(
+        &self,
+        _id: ResourceId,
+    ) -> Option<PinnedFuture<(ResourceId, String)>> {
+        None
+    }
+
+    #[cfg(feature = "debug")]
+    fn graph_edges(&self) -> (crate::SignalId, crate::SignalId, crate::SignalId) {
+        (self.0.source.0.id, self.0.value.id, self.0.loading.id)
     }
 }
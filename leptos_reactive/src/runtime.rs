@@ -1,34 +1,70 @@
+#[cfg(feature = "debug")]
+use crate::graph::{EffectRuns, GraphNode};
+use crate::sync::{AnyValue, BoxedCleanup, Cell, RefCell, Shared, ThreadSafe};
+#[cfg(feature = "transition")]
+use crate::Transition;
 use crate::{
-    hydration::SharedContext, AnyEffect, AnyResource, Effect, EffectId, Memo, ReadSignal,
-    ResourceId, ResourceState, RwSignal, Scope, ScopeDisposer, ScopeId, ScopeProperty, SignalId,
-    WriteSignal,
+    context::ContextId, hydration::SharedContext, AnyEffect, AnyResource, Effect, EffectId,
+    EffectState, Executor, Memo, MemoState, ReadSignal, ResourceId, ResourceState, RwSignal, Scope,
+    ScopeDisposer, ScopeId, ScopeProperty, SignalError, SignalId, WriteSignal,
 };
-use serde::{de::DeserializeOwned, Serialize};
 use slotmap::{SecondaryMap, SlotMap, SparseSecondaryMap};
 use std::{
-    any::{Any, TypeId},
-    cell::{Cell, RefCell},
+    any::Any,
     collections::{HashMap, HashSet},
     fmt::Debug,
     marker::PhantomData,
-    rc::Rc,
 };
 
 #[derive(Default)]
 pub(crate) struct Runtime {
+    /// Held by whichever thread is using the runtime (see [with_runtime]), so that its
+    /// observer, batching state and effect queue are never shared by two threads at once.
+    #[cfg(feature = "multithreaded")]
+    pub lock: std::sync::ReentrantLock<()>,
     pub shared_context: RefCell<Option<SharedContext>>,
     pub observer: Cell<Option<EffectId>>,
     pub scopes: RefCell<SlotMap<ScopeId, RefCell<Vec<ScopeProperty>>>>,
     pub scope_parents: RefCell<SparseSecondaryMap<ScopeId, ScopeId>>,
     pub scope_children: RefCell<SparseSecondaryMap<ScopeId, RefCell<Vec<ScopeId>>>>,
     #[allow(clippy::type_complexity)]
-    pub scope_contexts: RefCell<SparseSecondaryMap<ScopeId, HashMap<TypeId, Box<dyn Any>>>>,
-    pub signals: RefCell<SlotMap<SignalId, Rc<RefCell<dyn Any>>>>,
+    pub scope_contexts: RefCell<SparseSecondaryMap<ScopeId, HashMap<ContextId, Box<AnyValue>>>>,
+    pub signals: RefCell<SlotMap<SignalId, Shared<RefCell<AnyValue>>>>,
     pub signal_subscribers: RefCell<SecondaryMap<SignalId, RefCell<HashSet<EffectId>>>>,
-    pub effects: RefCell<SlotMap<EffectId, Rc<RefCell<dyn AnyEffect>>>>,
+    /// Custom equality checks, for signals created with `create_signal_with_eq`. Each is the
+    /// `SignalEq<T>` for the signal’s type.
+    pub signal_eqs: RefCell<SecondaryMap<SignalId, Box<AnyValue>>>,
+    pub effects: RefCell<SlotMap<EffectId, Shared<RefCell<dyn AnyEffect>>>>,
     pub effect_sources: RefCell<SecondaryMap<EffectId, RefCell<HashSet<SignalId>>>>,
+    pub effect_states: RefCell<SecondaryMap<EffectId, EffectState>>,
+    pub effect_cleanups: RefCell<SecondaryMap<EffectId, Vec<BoxedCleanup>>>,
+    /// The scope each effect or memo was created in.
+    pub effect_owners: RefCell<SecondaryMap<EffectId, ScopeId>>,
+    /// The scope that owns whatever was created by the latest run of each effect or memo.
+    pub effect_scopes: RefCell<SecondaryMap<EffectId, ScopeId>>,
+    /// Maps the signal that holds each memo’s value to the computation that updates it.
+    pub memos: RefCell<SecondaryMap<SignalId, EffectId>>,
+    /// Maps each memo’s computation to the signal that holds its value.
+    pub memo_signals: RefCell<SecondaryMap<EffectId, SignalId>>,
+    pub batching: Cell<bool>,
+    pub running_effects: Cell<bool>,
+    pub pending_effects: RefCell<Vec<EffectId>>,
+    /// Runs background work instead of the default `spawn_local` and `queue_microtask`.
+    pub executor: RefCell<Option<Shared<dyn Executor>>>,
     #[cfg(feature = "resource")]
-    pub resources: RefCell<SlotMap<ResourceId, Rc<dyn AnyResource>>>,
+    pub resources: RefCell<SlotMap<ResourceId, Shared<dyn AnyResource>>>,
+    /// The transition that any resources that start loading now belong to.
+    #[cfg(feature = "transition")]
+    pub transition: Cell<Option<Transition>>,
+    /// Names given to parts of the graph with [DebugName](crate::DebugName).
+    #[cfg(feature = "debug")]
+    pub debug_names: RefCell<HashMap<GraphNode, String>>,
+    /// How many times each effect or memo has run, and which signal caused its latest run.
+    #[cfg(feature = "debug")]
+    pub effect_runs: RefCell<SecondaryMap<EffectId, EffectRuns>>,
+    /// Where each part of the graph was created.
+    #[cfg(feature = "debug")]
+    pub locations: RefCell<HashMap<GraphNode, &'static std::panic::Location<'static>>>,
 }
 
 impl Debug for Runtime {
@@ -43,44 +79,151 @@ impl Debug for Runtime {
             .field("signal_subscribers", &self.signal_subscribers)
             .field("effects", &self.effects.borrow().len())
             .field("effect_sources", &self.effect_sources)
+            .field("effect_states", &self.effect_states)
+            .field("memos", &self.memos)
+            .field("batching", &self.batching)
+            .field("pending_effects", &self.pending_effects)
             .finish()
     }
 }
 
-impl Runtime {
-    pub fn new() -> Self {
-        Self::default()
+#[cfg(not(feature = "multithreaded"))]
+thread_local! {
+    /// Every reactive [Runtime] that is currently alive on this thread.
+    static RUNTIMES: RefCell<SlotMap<RuntimeId, Shared<Runtime>>> = Default::default();
+}
+
+/// Every reactive [Runtime] that is currently alive.
+#[cfg(feature = "multithreaded")]
+static RUNTIMES: std::sync::LazyLock<RefCell<SlotMap<RuntimeId, Shared<Runtime>>>> =
+    std::sync::LazyLock::new(Default::default);
+
+fn with_runtimes<T>(f: impl FnOnce(&RefCell<SlotMap<RuntimeId, Shared<Runtime>>>) -> T) -> T {
+    #[cfg(not(feature = "multithreaded"))]
+    {
+        RUNTIMES.with(f)
+    }
+    #[cfg(feature = "multithreaded")]
+    {
+        f(&RUNTIMES)
+    }
+}
+
+thread_local! {
+    /// Whether signals read on this thread are currently untracked. See [untrack](crate::untrack).
+    static UNTRACKED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Runs the given function with signals read on this thread tracked or untracked, restoring
+/// the previous setting afterwards, even if `f` panics.
+pub(crate) fn with_untracked<T>(untracked: bool, f: impl FnOnce() -> T) -> T {
+    struct RestoreUntracked(bool);
+
+    impl Drop for RestoreUntracked {
+        fn drop(&mut self) {
+            UNTRACKED.with(|untracked| untracked.set(self.0));
+        }
+    }
+
+    let _restore = RestoreUntracked(UNTRACKED.with(|prev| prev.replace(untracked)));
+    f()
+}
+
+slotmap::new_key_type! {
+    /// Unique ID assigned to a [Runtime].
+    pub(crate) struct RuntimeId;
+}
+
+/// Creates a new reactive [Runtime]. It is owned by the runtime registry (which is
+/// thread-local, unless the `multithreaded` feature is enabled) until
+/// [RuntimeId::dispose] is called.
+pub(crate) fn create_runtime() -> RuntimeId {
+    with_runtimes(|runtimes| runtimes.borrow_mut().insert(Shared::new(Runtime::new())))
+}
+
+/// Runs the given function with the [Runtime] for the given ID.
+///
+/// With the `multithreaded` feature, only one thread can use a runtime at a time: another
+/// thread that calls this waits until the first one's `f` (including any effects it runs)
+/// has returned. The same thread can re-enter the runtime from within `f`.
+///
+/// # Panics
+/// Panics if the runtime has already been disposed.
+pub(crate) fn with_runtime<T>(id: RuntimeId, f: impl FnOnce(&Runtime) -> T) -> T {
+    // clone the pointer rather than holding the borrow, so `f` can create or dispose runtimes
+    let runtime = with_runtimes(|runtimes| runtimes.borrow().get(id).cloned());
+    let runtime = runtime.unwrap_or_else(|| {
+        panic!("tried to access a reactive runtime that has been disposed: {id:?}")
+    });
+    #[cfg(feature = "multithreaded")]
+    let _lock = runtime.lock.lock();
+    f(&runtime)
+}
+
+/// Runs the given function with the [Runtime] for the given ID, or returns an error if the
+/// runtime has already been disposed.
+pub(crate) fn try_with_runtime<T>(
+    id: RuntimeId,
+    f: impl FnOnce(&Runtime) -> T,
+) -> Result<T, SignalError> {
+    let runtime = with_runtimes(|runtimes| runtimes.borrow().get(id).cloned())
+        .ok_or(SignalError::RuntimeDisposed)?;
+    #[cfg(feature = "multithreaded")]
+    let _lock = runtime.lock.lock();
+    Ok(f(&runtime))
+}
+
+impl RuntimeId {
+    /// Removes the runtime from the registry, dropping everything it owns.
+    pub(crate) fn dispose(self) {
+        let runtime = with_runtimes(|runtimes| runtimes.borrow_mut().remove(self));
+        drop(runtime);
     }
 
-    pub fn run_scope_undisposed<T>(
-        &'static self,
+    #[track_caller]
+    pub(crate) fn run_scope_undisposed<T>(
+        self,
         f: impl FnOnce(Scope) -> T,
         parent: Option<Scope>,
     ) -> (T, ScopeDisposer) {
-        let id = { self.scopes.borrow_mut().insert(Default::default()) };
-        if let Some(parent) = parent {
-            self.scope_parents.borrow_mut().insert(id, parent.id);
-        }
+        #[cfg(feature = "debug")]
+        let location = std::panic::Location::caller();
+        let id = with_runtime(self, |runtime| {
+            let id = { runtime.scopes.borrow_mut().insert(Default::default()) };
+            #[cfg(feature = "debug")]
+            runtime.record_location(id.into(), location);
+            if let Some(parent) = parent {
+                runtime.scope_parents.borrow_mut().insert(id, parent.id);
+                // register as a child, so disposing of the parent disposes of this scope too
+                if let Some(children) = runtime.scope_children.borrow_mut().entry(parent.id) {
+                    children.or_default().borrow_mut().push(id);
+                }
+            }
+            id
+        });
         let scope = Scope { runtime: self, id };
         let val = f(scope);
         let disposer = ScopeDisposer(Box::new(move || scope.dispose()));
         (val, disposer)
     }
 
-    pub fn run_scope<T>(&'static self, f: impl FnOnce(Scope) -> T, parent: Option<Scope>) -> T {
+    #[track_caller]
+    pub(crate) fn run_scope<T>(self, f: impl FnOnce(Scope) -> T, parent: Option<Scope>) -> T {
         let (ret, disposer) = self.run_scope_undisposed(f, parent);
         disposer.dispose();
         ret
     }
 
-    pub(crate) fn create_signal<T>(&'static self, value: T) -> (ReadSignal<T>, WriteSignal<T>)
+    pub(crate) fn create_signal<T>(self, value: T) -> (ReadSignal<T>, WriteSignal<T>)
     where
-        T: Any + 'static,
+        T: Any + ThreadSafe + 'static,
     {
-        let id = self
-            .signals
-            .borrow_mut()
-            .insert(Rc::new(RefCell::new(value)));
+        let id = with_runtime(self, |runtime| {
+            runtime
+                .signals
+                .borrow_mut()
+                .insert(Shared::new(RefCell::new(value)))
+        });
         (
             ReadSignal {
                 runtime: self,
@@ -95,14 +238,16 @@ impl Runtime {
         )
     }
 
-    pub(crate) fn create_rw_signal<T>(&'static self, value: T) -> RwSignal<T>
+    pub(crate) fn create_rw_signal<T>(self, value: T) -> RwSignal<T>
     where
-        T: Any + 'static,
+        T: Any + ThreadSafe + 'static,
     {
-        let id = self
-            .signals
-            .borrow_mut()
-            .insert(Rc::new(RefCell::new(value)));
+        let id = with_runtime(self, |runtime| {
+            runtime
+                .signals
+                .borrow_mut()
+                .insert(Shared::new(RefCell::new(value)))
+        });
         RwSignal {
             runtime: self,
             id,
@@ -110,49 +255,406 @@ impl Runtime {
         }
     }
 
-    pub(crate) fn create_effect<T>(
-        &'static self,
-        f: impl FnMut(Option<T>) -> T + 'static,
-    ) -> EffectId
+    pub(crate) fn create_memo<T>(
+        self,
+        owner: ScopeId,
+        f: impl FnMut(Option<T>) -> T + ThreadSafe + 'static,
+    ) -> (Memo<T>, EffectId)
     where
-        T: Any + 'static,
+        T: Clone + PartialEq + Any + ThreadSafe + 'static,
     {
-        let effect = Effect { f, value: None };
-        let id = {
-            self.effects
+        let (read, _) = self.create_signal(None::<T>);
+        let state = MemoState {
+            f,
+            signal: read.id,
+            ty: PhantomData,
+        };
+        let id = with_runtime(self, |runtime| {
+            let id = {
+                runtime
+                    .effects
+                    .borrow_mut()
+                    .insert(Shared::new(RefCell::new(state)))
+            };
+            runtime
+                .effect_states
                 .borrow_mut()
-                .insert(Rc::new(RefCell::new(effect)))
+                .insert(id, EffectState::Dirty);
+            runtime.memos.borrow_mut().insert(read.id, id);
+            runtime.memo_signals.borrow_mut().insert(id, read.id);
+            runtime.effect_owners.borrow_mut().insert(id, owner);
+            id
+        });
+
+        // the calculation doesn't run until the memo is first read
+        (Memo(read), id)
+    }
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs the given function with effect execution deferred: effects notified by
+    /// any signal updates within `f` are queued, and each of them runs once when the
+    /// outermost batch ends.
+    ///
+    /// The previous batching state is restored even if `f` panics, so that a panic caught
+    /// outside the batch doesn't leave effects deferred from then on.
+    pub(crate) fn batch<T>(&self, f: impl FnOnce() -> T) -> T {
+        struct RestoreBatching<'a> {
+            runtime: &'a Runtime,
+            was_batching: bool,
+        }
+
+        impl Drop for RestoreBatching<'_> {
+            fn drop(&mut self) {
+                self.runtime.batching.set(self.was_batching);
+            }
+        }
+
+        let was_batching = self.batching.replace(true);
+        #[cfg(feature = "transition")]
+        let prev_transition = self.transition.get();
+        let val = {
+            let _restore = RestoreBatching {
+                runtime: self,
+                was_batching,
+            };
+            f()
         };
-        id.run::<T>(self);
-        id
+        if !was_batching {
+            self.run_effects();
+            // a transition started within the batch lasts until its effects have run
+            #[cfg(feature = "transition")]
+            self.transition.set(prev_transition);
+        }
+        val
     }
 
-    pub(crate) fn create_memo<T>(
-        &'static self,
-        mut f: impl FnMut(Option<T>) -> T + 'static,
-    ) -> Memo<T>
-    where
-        T: Clone + PartialEq + Any + 'static,
-    {
-        let (read, write) = self.create_signal(None);
-        self.create_effect(move |prev| {
-            let new = { f(prev.clone()) };
+    /// Runs the given function with `observer` as the current observer, so that
+    /// any signals read within it are tracked as its sources.
+    ///
+    /// The previous observer is restored even if `f` panics, so that calls can be nested
+    /// (an effect that runs inside [untrack](crate::untrack) still tracks what it reads)
+    /// without one of them leaving the wrong observer in place.
+    pub(crate) fn with_observer<T>(&self, observer: Option<EffectId>, f: impl FnOnce() -> T) -> T {
+        struct RestoreObserver<'a> {
+            runtime: &'a Runtime,
+            prev_observer: Option<EffectId>,
+        }
 
-            if prev.as_ref() != Some(&new) {
-                write(Some(new.clone()));
+        impl Drop for RestoreObserver<'_> {
+            fn drop(&mut self) {
+                self.runtime.observer.set(self.prev_observer);
             }
-            new
-        });
+        }
 
-        Memo(read)
+        let _restore = RestoreObserver {
+            runtime: self,
+            prev_observer: self.observer.replace(observer),
+        };
+        if observer.is_some() {
+            with_untracked(false, f)
+        } else {
+            f()
+        }
     }
 
-    #[cfg(feature = "resource")]
-    pub(crate) fn create_resource<S, T>(&self, state: Rc<ResourceState<S, T>>) -> ResourceId
+    /// The effect or memo that is currently running and tracking the signals it reads, if any.
+    pub(crate) fn tracking_observer(&self) -> Option<EffectId> {
+        self.observer
+            .get()
+            .filter(|_| !UNTRACKED.with(|untracked| untracked.get()))
+    }
+
+    /// Runs and removes any cleanup functions registered during the effect’s last run.
+    pub(crate) fn run_effect_cleanups(&self, id: EffectId) {
+        let cleanups = self.effect_cleanups.borrow_mut().remove(id);
+        for cleanup in cleanups.into_iter().flatten() {
+            cleanup();
+        }
+    }
+
+    /// The scope that anything created in `scope` right now belongs to.
+    ///
+    /// While an effect or memo that was created in `scope` is running (and not inside
+    /// [untrack](crate::untrack)), this is a child scope owned by that run, which is
+    /// disposed before it runs again. Otherwise, it is `scope` itself.
+    pub(crate) fn owning_scope(&self, scope: ScopeId) -> ScopeId {
+        let effect = match self.tracking_observer() {
+            Some(effect) if self.effect_owners.borrow().get(effect) == Some(&scope) => effect,
+            _ => return scope,
+        };
+        if let Some(owned) = self.effect_scopes.borrow().get(effect) {
+            return *owned;
+        }
+
+        // a child of `scope`, so contexts can still be found, but only the effect disposes it
+        let owned = self.scopes.borrow_mut().insert(Default::default());
+        self.scope_parents.borrow_mut().insert(owned, scope);
+        self.effect_scopes.borrow_mut().insert(effect, owned);
+        owned
+    }
+
+    /// Disposes of everything created by the effect’s last run.
+    pub(crate) fn dispose_effect_scope(&self, id: EffectId) {
+        let owned = self.effect_scopes.borrow_mut().remove(id);
+        if let Some(owned) = owned {
+            self.dispose_scope(owned);
+        }
+    }
+
+    /// Disposes of the scope and its child scopes, along with everything they own.
+    pub(crate) fn dispose_scope(&self, id: ScopeId) {
+        // dispose of all child scopes
+        let children = {
+            let mut children = self.scope_children.borrow_mut();
+            let children = children.remove(id);
+            children.map(|children| children.take())
+        };
+
+        if let Some(children) = children {
+            for child in children {
+                self.dispose_scope(child);
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        self.forget_debug_info(id.into());
+
+        // remove everything we own and run cleanups
+        let owned = {
+            let owned = self.scopes.borrow_mut().remove(id);
+            owned.map(|owned| owned.take())
+        };
+        if let Some(owned) = owned {
+            for property in owned {
+                match property {
+                    ScopeProperty::Signal(id) => {
+                        #[cfg(feature = "debug")]
+                        self.forget_debug_info(id.into());
+                        self.signals.borrow_mut().remove(id);
+                        self.signal_subscribers.borrow_mut().remove(id);
+                        self.signal_eqs.borrow_mut().remove(id);
+                        self.memos.borrow_mut().remove(id);
+                    }
+                    ScopeProperty::Effect(id) => {
+                        #[cfg(feature = "debug")]
+                        self.forget_debug_info(id.into());
+                        self.run_effect_cleanups(id);
+                        self.dispose_effect_scope(id);
+                        id.cleanup(self);
+                        self.effects.borrow_mut().remove(id);
+                        self.effect_sources.borrow_mut().remove(id);
+                        self.effect_states.borrow_mut().remove(id);
+                        self.effect_owners.borrow_mut().remove(id);
+                        self.memo_signals.borrow_mut().remove(id);
+                    }
+                    ScopeProperty::Resource(id) => {
+                        #[cfg(feature = "debug")]
+                        self.forget_debug_info(id.into());
+                        self.resources.borrow_mut().remove(id);
+                    }
+                    ScopeProperty::Cleanup(f) => f(),
+                    ScopeProperty::Tasks(tasks) => {
+                        for task in tasks {
+                            task.abort();
+                        }
+                    }
+                }
+            }
+        }
+        self.scope_parents.borrow_mut().remove(id);
+    }
+
+    /// Notifies everything that depends on the signal that its value has changed.
+    ///
+    /// Effects and memos that read the signal directly are marked dirty, and everything
+    /// downstream of those memos is marked to be checked, but nothing re-runs yet:
+    /// effects are queued and run by [Runtime::run_effects].
+    pub(crate) fn mark_dirty(&self, signal: SignalId) {
+        let subs = {
+            let subs = self.signal_subscribers.borrow();
+            subs.get(signal).map(|subs| subs.borrow().clone())
+        };
+        for sub in subs.into_iter().flatten() {
+            #[cfg(feature = "debug")]
+            self.record_trigger(sub, signal);
+            self.mark(sub, EffectState::Dirty);
+        }
+    }
+
+    /// Records the signal that marked an effect dirty, unless it is already going to re-run.
+    #[cfg(feature = "debug")]
+    fn record_trigger(&self, id: EffectId, signal: SignalId) {
+        if self.effect_states.borrow().get(id) == Some(&EffectState::Dirty) {
+            return;
+        }
+        if let Some(runs) = self.effect_runs.borrow_mut().entry(id) {
+            runs.or_default().triggered_by = Some(signal);
+        }
+    }
+
+    fn mark(&self, id: EffectId, level: EffectState) {
+        let prev = {
+            let mut states = self.effect_states.borrow_mut();
+            match states.get_mut(id) {
+                Some(state) => {
+                    let prev = *state;
+                    *state = prev.max(level);
+                    prev
+                }
+                // the effect has been disposed
+                None => return,
+            }
+        };
+
+        // if it was already marked, everything downstream has been marked too
+        if prev != EffectState::Clean {
+            return;
+        }
+
+        let memo_signal = self.memo_signals.borrow().get(id).copied();
+        match memo_signal {
+            // memos are lazy: they don't re-run until they are read, but anything that
+            // reads them may need to re-run if their value changes
+            Some(signal) => {
+                let subs = {
+                    let subs = self.signal_subscribers.borrow();
+                    subs.get(signal).map(|subs| subs.borrow().clone())
+                };
+                for sub in subs.into_iter().flatten() {
+                    self.mark(sub, EffectState::Check);
+                }
+            }
+            None => self.pending_effects.borrow_mut().push(id),
+        }
+    }
+
+    /// Brings an effect or memo up to date: if any memos it depends on may have changed,
+    /// they are updated first (in dependency order), and then it re-runs only if one of
+    /// its sources has actually changed.
+    pub(crate) fn update_if_necessary(&self, id: EffectId) {
+        let state = self.effect_states.borrow().get(id).copied();
+
+        if state == Some(EffectState::Check) {
+            let sources = {
+                let sources = self.effect_sources.borrow();
+                sources
+                    .get(id)
+                    .map(|sources| sources.borrow().iter().copied().collect::<Vec<_>>())
+            };
+            for source in sources.into_iter().flatten() {
+                let memo = self.memos.borrow().get(source).copied();
+                if let Some(memo) = memo {
+                    self.update_if_necessary(memo);
+                }
+                // if the memo changed, it will have marked this dirty
+                if self.effect_states.borrow().get(id) == Some(&EffectState::Dirty) {
+                    break;
+                }
+            }
+        }
+
+        // mark it clean before running, so that any changes made while it runs mark it again
+        let state = self
+            .effect_states
+            .borrow_mut()
+            .get_mut(id)
+            .map(|state| std::mem::replace(state, EffectState::Clean));
+
+        if state == Some(EffectState::Dirty) {
+            #[cfg(feature = "debug")]
+            if let Some(runs) = self.effect_runs.borrow_mut().entry(id) {
+                runs.or_default().runs += 1;
+            }
+
+            let changed = id.run(self);
+
+            // a memo that has changed marks anything that reads it dirty
+            let memo_signal = self.memo_signals.borrow().get(id).copied();
+            if let (true, Some(signal)) = (changed, memo_signal) {
+                self.mark_dirty(signal);
+            }
+        }
+    }
+
+    /// Runs every queued effect, unless we are in a batch or already running effects.
+    pub(crate) fn run_effects(&self) {
+        if self.batching.get() || self.running_effects.get() {
+            return;
+        }
+
+        // stops running effects even if one of them panics, so that later updates still
+        // run their effects
+        struct StopRunning<'a>(&'a Runtime);
+
+        impl Drop for StopRunning<'_> {
+            fn drop(&mut self) {
+                self.0.running_effects.set(false);
+            }
+        }
+
+        #[cfg(feature = "transition")]
+        let prev_transition = self.transition.get();
+        self.running_effects.set(true);
+        {
+            let _stop = StopRunning(self);
+            loop {
+                let effects = self.pending_effects.take();
+                if effects.is_empty() {
+                    break;
+                }
+                for id in effects {
+                    self.update_if_necessary(id);
+                }
+            }
+        }
+
+        // a transition started within an effect lasts until its effects have run
+        #[cfg(feature = "transition")]
+        self.transition.set(prev_transition);
+    }
+
+    /// Runs the given function as part of the transition, which collects any resources
+    /// that start loading, either within `f` or in the effects that run as a result.
+    #[cfg(feature = "transition")]
+    pub(crate) fn start_transition(&self, transition: Transition, f: impl FnOnce()) {
+        let prev = self.transition.replace(Some(transition));
+        self.batch(f);
+        // if we're inside another batch or an effect, the effects `f` caused haven't run yet,
+        // so the transition stays active until they have (see `batch` and `run_effects`)
+        if !self.batching.get() && !self.running_effects.get() {
+            self.transition.set(prev);
+        }
+    }
+
+    pub(crate) fn create_effect<T>(
+        &self,
+        owner: ScopeId,
+        f: impl FnMut(Option<T>) -> T + ThreadSafe + 'static,
+    ) -> EffectId
     where
-        S: Debug + Clone + 'static,
-        T: Debug + Clone + Serialize + DeserializeOwned + 'static,
+        T: Any + ThreadSafe + 'static,
     {
+        let effect = Effect { f, value: None };
+        let id = {
+            self.effects
+                .borrow_mut()
+                .insert(Shared::new(RefCell::new(effect)))
+        };
+        self.effect_states
+            .borrow_mut()
+            .insert(id, EffectState::Dirty);
+        self.effect_owners.borrow_mut().insert(id, owner);
+        self.update_if_necessary(id);
+        id
+    }
+
+    #[cfg(feature = "resource")]
+    pub(crate) fn create_resource(&self, state: Shared<dyn AnyResource>) -> ResourceId {
         self.resources.borrow_mut().insert(state)
     }
 
@@ -194,29 +696,43 @@ impl Runtime {
         S: Debug + Clone + 'static,
         T: Debug + Clone + 'static,
     {
-        let resources = self.resources.borrow();
-        let res = resources.get(id);
-        if let Some(res) = res {
-            if let Some(n) = res.as_any().downcast_ref::<ResourceState<S, T>>() {
-                f(n)
-            } else {
-                panic!(
-                    "couldn't convert {id:?} to ResourceState<{}, {}>",
-                    std::any::type_name::<S>(),
-                    std::any::type_name::<T>(),
-                );
-            }
+        self.try_resource(id, f)
+            .unwrap_or_else(|_| panic!("couldn't locate {id:?}"))
+    }
+
+    /// Like [Runtime::resource], but returns an error if the resource has been disposed.
+    #[cfg(feature = "resource")]
+    pub(crate) fn try_resource<S, T, U>(
+        &self,
+        id: ResourceId,
+        f: impl FnOnce(&ResourceState<S, T>) -> U,
+    ) -> Result<U, SignalError>
+    where
+        S: Debug + Clone + 'static,
+        T: Debug + Clone + 'static,
+    {
+        // clone it out, so that `f` can access other resources
+        let res = self.resources.borrow().get(id).cloned();
+        let res = res.ok_or(SignalError::ResourceDisposed(id))?;
+        if let Some(n) = res.as_any().downcast_ref::<ResourceState<S, T>>() {
+            Ok(f(n))
         } else {
-            panic!("couldn't locate {id:?}");
+            panic!(
+                "couldn't convert {id:?} to ResourceState<{}, {}>",
+                std::any::type_name::<S>(),
+                std::any::type_name::<T>(),
+            );
         }
     }
 
-    /// Returns IDs for all [Resource]s found on any scope.
+    /// Returns IDs for all [Resource]s found on any scope, except local resources, which
+    /// are never sent from the server to the client.
     #[cfg(feature = "resource")]
     pub(crate) fn all_resources(&self) -> Vec<ResourceId> {
         self.resources
             .borrow()
             .iter()
+            .filter(|(_, resource)| !resource.is_local())
             .map(|(resource_id, _)| resource_id)
             .collect()
     }
@@ -225,26 +741,56 @@ impl Runtime {
     pub(crate) fn serialization_resolvers(
         &self,
     ) -> futures::stream::futures_unordered::FuturesUnordered<
-        std::pin::Pin<Box<dyn futures::Future<Output = (ResourceId, String)>>>,
+        crate::PinnedFuture<(ResourceId, String)>,
     > {
         let f = futures::stream::futures_unordered::FuturesUnordered::new();
         for (id, resource) in self.resources.borrow().iter() {
-            f.push(resource.to_serialization_resolver(id));
+            if let Some(resolver) = resource.to_serialization_resolver(id) {
+                f.push(resolver);
+            }
         }
         f
     }
 }
 
-impl PartialEq for Runtime {
-    fn eq(&self, other: &Self) -> bool {
-        std::ptr::eq(self, other)
-    }
+/// The current time, as the time since the Unix epoch.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> std::time::Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// The current time, as the time since the Unix epoch.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> std::time::Duration {
+    std::time::Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
 }
 
-impl Eq for Runtime {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disposing_root_scope_removes_runtime_from_registry() {
+        let runtime = create_runtime();
+        let (cx, _) = runtime.run_scope_undisposed(|cx| cx, None);
+        let child = cx.child_scope(|_| {});
+
+        // disposing a child leaves the runtime alone
+        child.dispose();
+        assert!(with_runtimes(|runtimes| runtimes
+            .borrow()
+            .contains_key(runtime)));
+
+        cx.dispose();
+        assert!(!with_runtimes(|runtimes| runtimes
+            .borrow()
+            .contains_key(runtime)));
+        #[cfg(not(feature = "multithreaded"))]
+        assert!(with_runtimes(|runtimes| runtimes.borrow().is_empty()));
 
-impl std::hash::Hash for Runtime {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        std::ptr::hash(&self, state);
+        // disposing it again is a no-op
+        cx.dispose();
     }
 }
@@ -1,6 +1,9 @@
 use crate::{
-    hydration::SharedContext, AnyEffect, AnyResource, EffectId, ResourceId, ResourceState, Runtime,
-    SignalId,
+    create_runtime,
+    hydration::SharedContext,
+    sync::{BoxedCleanup, ThreadSafe},
+    try_with_runtime, with_runtime, with_untracked, AnyEffect, AnyResource, EffectId, ResourceId,
+    ResourceState, RuntimeId, SignalId, TaskHandle,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
@@ -10,16 +13,18 @@ use std::{
     fmt::Debug,
     rc::Rc,
 };
+
 #[cfg(feature = "ssr")]
-use std::{future::Future, pin::Pin};
+use crate::PinnedFuture;
 
 #[must_use = "Scope will leak memory if the disposer function is never called"]
 /// Creates a child reactive scope and runs the function within it. This is useful for applications
 /// like a list or a router, which may want to create child scopes and dispose of them when
 /// they are no longer needed (e.g., a list item has been destroyed or the user has navigated away
 /// from the route.)
+#[track_caller]
 pub fn create_scope(f: impl FnOnce(Scope) + 'static) -> ScopeDisposer {
-    let runtime = Box::leak(Box::new(Runtime::new()));
+    let runtime = create_runtime();
     runtime.run_scope_undisposed(f, None).1
 }
 
@@ -27,18 +32,18 @@ pub fn create_scope(f: impl FnOnce(Scope) + 'static) -> ScopeDisposer {
 /// and returns the value returned from the function. This is very useful for short-lived
 /// applications like SSR, where actual reactivity is not required beyond the end
 /// of the synchronous operation.
+#[track_caller]
 pub fn run_scope<T>(f: impl FnOnce(Scope) -> T + 'static) -> T {
-    // TODO this leaks the runtime — should unsafely upgrade the lifetime, and then drop it after the scope is run
-    let runtime = Box::leak(Box::new(Runtime::new()));
+    let runtime = create_runtime();
     runtime.run_scope(f, None)
 }
 
 #[must_use = "Scope will leak memory if the disposer function is never called"]
 /// Creates a temporary scope and run the given function without disposing of the scope.
 /// If you do not dispose of the scope on your own, memory will leak.
+#[track_caller]
 pub fn run_scope_undisposed<T>(f: impl FnOnce(Scope) -> T + 'static) -> (T, ScopeDisposer) {
-    // TODO this leaks the runtime — should unsafely upgrade the lifetime, and then drop it after the scope is run
-    let runtime = Box::leak(Box::new(Runtime::new()));
+    let runtime = create_runtime();
     runtime.run_scope_undisposed(f, None)
 }
 
@@ -57,9 +62,12 @@ pub fn run_scope_undisposed<T>(f: impl FnOnce(Scope) -> T + 'static) -> (T, Scop
 ///
 /// Every other function in this crate takes a `Scope` as its first argument. Since `Scope`
 /// is [Copy] and `'static` this does not add much overhead or lifetime complexity.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///
+/// Each root scope (created by [create_scope], [run_scope], or [run_scope_undisposed])
+/// has its own reactive runtime, which is freed when the root scope is disposed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Scope {
-    pub(crate) runtime: &'static Runtime,
+    pub(crate) runtime: RuntimeId,
     pub(crate) id: ScopeId,
 }
 
@@ -68,71 +76,204 @@ impl Scope {
         self.id
     }
 
+    #[track_caller]
     pub fn child_scope(self, f: impl FnOnce(Scope)) -> ScopeDisposer {
         let (_, disposer) = self.runtime.run_scope_undisposed(f, Some(self));
         disposer
     }
 
     pub fn untrack<T>(&self, f: impl FnOnce() -> T) -> T {
-        let prev_observer = self.runtime.observer.take();
-        let untracked_result = f();
-        self.runtime.observer.set(prev_observer);
-        untracked_result
+        with_runtime(self.runtime, |runtime| runtime.with_observer(None, f))
+    }
+
+    /// Runs the given function as a single batch of updates. See [batch].
+    pub fn batch<T>(&self, f: impl FnOnce() -> T) -> T {
+        with_runtime(self.runtime, |runtime| runtime.batch(f))
     }
 }
 
-// Internals
+/// Batches together any signal updates made within the given function, so that
+/// the effects that depend on them run only once, after the outermost batch has ended,
+/// rather than once for every update.
+///
+/// The Leptos DOM renderer runs every event handler in a batch, so setting several
+/// signals in a `click` handler only re-renders once.
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+/// # create_scope(|cx| {
+/// let (first, set_first) = create_signal(cx, "Bob".to_string());
+/// let (last, set_last) = create_signal(cx, "Smith".to_string());
+///
+/// let runs = Arc::new(AtomicUsize::new(0));
+/// create_effect(cx, {
+///   let runs = Arc::clone(&runs);
+///   move |_| {
+///     let name = format!("{} {}", first(), last());
+///     runs.fetch_add(1, Ordering::Relaxed);
+///     name
+///   }
+/// });
+/// assert_eq!(runs.load(Ordering::Relaxed), 1);
+///
+/// batch(cx, || {
+///   set_first("Alice".to_string());
+///   set_last("Jones".to_string());
+///   // the effect hasn't run yet
+///   assert_eq!(runs.load(Ordering::Relaxed), 1);
+/// });
+///
+/// // ✅ the effect ran once for both updates
+/// assert_eq!(runs.load(Ordering::Relaxed), 2);
+/// # }).dispose();
+/// ```
+pub fn batch<T>(cx: Scope, f: impl FnOnce() -> T) -> T {
+    cx.batch(f)
+}
 
-impl Scope {
-    pub fn dispose(self) {
-        // dispose of all child scopes
-        let children = {
-            let mut children = self.runtime.scope_children.borrow_mut();
-            let children = children.remove(self.id);
-            children.map(|children| children.take())
-        };
+/// Runs the given function without tracking the signals it reads, so the effect or memo
+/// that is currently running won’t re-run when they change.
+///
+/// Unlike [Scope::untrack], this doesn’t need a [Scope], so it also works in code that only
+/// has signals. Effects and memos that run inside `f` (for example, because `f` sets a signal)
+/// still track what they read as usual.
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+/// # create_scope(|cx| {
+/// let (a, set_a) = create_signal(cx, 0);
+/// let (b, set_b) = create_signal(cx, 0);
+///
+/// let runs = Arc::new(AtomicUsize::new(0));
+/// create_isomorphic_effect(cx, {
+///   let runs = Arc::clone(&runs);
+///   move |_| {
+///     // re-runs when `a` changes, but not when `b` does
+///     let sum = a() + untrack(|| b());
+///     runs.fetch_add(1, Ordering::Relaxed);
+///     sum
+///   }
+/// });
+///
+/// set_b(1);
+/// assert_eq!(runs.load(Ordering::Relaxed), 1);
+/// set_a(1);
+/// assert_eq!(runs.load(Ordering::Relaxed), 2);
+/// # }).dispose();
+/// ```
+pub fn untrack<T>(f: impl FnOnce() -> T) -> T {
+    with_untracked(true, f)
+}
 
-        if let Some(children) = children {
-            for id in children {
-                Scope {
-                    runtime: self.runtime,
-                    id,
-                }
-                .dispose();
+/// Registers a function to be run when the reactive [Scope] is disposed, or when the
+/// effect that is currently running runs again.
+///
+/// This is useful for tearing down anything a component or effect has set up outside the
+/// reactive system, like intervals, event listeners, or subscriptions.
+///
+/// If `on_cleanup` is called while an effect is running (and not inside [untrack]),
+/// the cleanup function belongs to that run of the effect: it runs before the effect re-runs,
+/// or when the effect is disposed. Otherwise, it runs when the scope is disposed.
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::sync::{Arc, Mutex};
+/// let log = Arc::new(Mutex::new(Vec::new()));
+///
+/// create_scope({
+///   let log = Arc::clone(&log);
+///   move |cx| {
+///     let (id, set_id) = create_signal(cx, 1);
+///
+///     // runs when the scope is disposed
+///     on_cleanup(cx, {
+///       let log = Arc::clone(&log);
+///       move || log.lock().unwrap().push("scope disposed".to_string())
+///     });
+///
+///     create_effect(cx, {
+///       let log = Arc::clone(&log);
+///       move |_| {
+///         let id = id();
+///         log.lock().unwrap().push(format!("subscribe {id}"));
+///         // runs before the effect runs again
+///         on_cleanup(cx, {
+///           let log = Arc::clone(&log);
+///           move || log.lock().unwrap().push(format!("unsubscribe {id}"))
+///         });
+///       }
+///     });
+///
+///     set_id(2);
+///   }
+/// })
+/// .dispose();
+///
+/// assert_eq!(
+///   *log.lock().unwrap(),
+///   vec!["subscribe 1", "unsubscribe 1", "subscribe 2", "scope disposed", "unsubscribe 2"]
+/// );
+/// ```
+pub fn on_cleanup(cx: Scope, cleanup_fn: impl FnOnce() + ThreadSafe + 'static) {
+    let cleanup_fn = with_runtime(cx.runtime, |runtime| {
+        // only a cleanup registered in the scope the effect was created in belongs to
+        // its current run; a scope created inside the effect cleans up when it's disposed
+        let effect = runtime
+            .tracking_observer()
+            .filter(|effect| runtime.effect_owners.borrow().get(*effect) == Some(&cx.id));
+        if let Some(effect) = effect {
+            let mut cleanups = runtime.effect_cleanups.borrow_mut();
+            if let Some(cleanups) = cleanups.entry(effect) {
+                cleanups.or_default().push(Box::new(cleanup_fn));
+                return None;
             }
         }
+        Some(cleanup_fn)
+    });
+    if let Some(cleanup_fn) = cleanup_fn {
+        cx.with_scope_property(|prop| prop.push(ScopeProperty::Cleanup(Box::new(cleanup_fn))))
+    }
+}
 
-        // remove everything we own and run cleanups
-        let owned = {
-            let owned = self.runtime.scopes.borrow_mut().remove(self.id);
-            owned.map(|owned| owned.take())
-        };
-        if let Some(owned) = owned {
-            for property in owned {
-                match property {
-                    ScopeProperty::Signal(id) => {
-                        self.runtime.signals.borrow_mut().remove(id);
-                        self.runtime.signal_subscribers.borrow_mut().remove(id);
-                    }
-                    ScopeProperty::Effect(id) => {
-                        self.runtime.effects.borrow_mut().remove(id);
-                        self.runtime.effect_sources.borrow_mut().remove(id);
-                    }
-                    ScopeProperty::Resource(id) => {
-                        self.runtime.resources.borrow_mut().remove(id);
-                    }
-                    ScopeProperty::Cleanup(f) => (f()),
-                }
-            }
+// Internals
+
+impl Scope {
+    pub fn dispose(self) {
+        // a scope that was already disposed, or whose runtime was, has nothing left to free
+        let is_root = try_with_runtime(self.runtime, |runtime| {
+            let is_root = runtime.scopes.borrow().contains_key(self.id)
+                && !runtime.scope_parents.borrow().contains_key(self.id);
+            runtime.dispose_scope(self.id);
+            is_root
+        });
+        // the root scope owns the runtime, so disposing it frees the runtime as well
+        if let Ok(true) = is_root {
+            self.runtime.dispose();
         }
     }
 
+    #[track_caller]
     pub(crate) fn with_scope_property(&self, f: impl FnOnce(&mut Vec<ScopeProperty>)) {
-        let scopes = self.runtime.scopes.borrow();
-        let scope = scopes
-            .get(self.id)
-            .expect("tried to add property to a scope that has been disposed");
-        f(&mut *scope.borrow_mut());
+        #[cfg(feature = "debug")]
+        let location = std::panic::Location::caller();
+        with_runtime(self.runtime, |runtime| {
+            // anything created while an effect runs belongs to that run, if the effect
+            // was created in this scope
+            let id = runtime.owning_scope(self.id);
+            let scopes = runtime.scopes.borrow();
+            let scope = scopes
+                .get(id)
+                .expect("tried to add property to a scope that has been disposed");
+            #[cfg(feature = "debug")]
+            let prev_len = scope.borrow().len();
+            f(&mut scope.borrow_mut());
+            // remember where anything that was just added was created
+            #[cfg(feature = "debug")]
+            for property in &scope.borrow()[prev_len..] {
+                if let Some(node) = property.graph_node() {
+                    runtime.record_location(node, location);
+                }
+            }
+        })
     }
 }
 
@@ -142,7 +283,23 @@ pub(crate) enum ScopeProperty {
     Signal(SignalId),
     Effect(EffectId),
     Resource(ResourceId),
-    Cleanup(Box<dyn FnOnce()>),
+    Cleanup(BoxedCleanup),
+    /// Tasks spawned with [Scope::spawn](crate::Scope::spawn), aborted when the scope is
+    /// disposed. Finished ones are dropped whenever another is spawned.
+    Tasks(Vec<TaskHandle>),
+}
+
+#[cfg(feature = "debug")]
+impl ScopeProperty {
+    /// The part of the reactive graph this refers to, if any.
+    pub(crate) fn graph_node(&self) -> Option<crate::graph::GraphNode> {
+        match self {
+            Self::Signal(id) => Some((*id).into()),
+            Self::Effect(id) => Some((*id).into()),
+            Self::Resource(id) => Some((*id).into()),
+            Self::Cleanup(_) | Self::Tasks(_) => None,
+        }
+    }
 }
 
 impl Debug for ScopeProperty {
@@ -152,11 +309,12 @@ impl Debug for ScopeProperty {
             Self::Effect(arg0) => f.debug_tuple("Effect").field(arg0).finish(),
             Self::Resource(arg0) => f.debug_tuple("Resource").field(arg0).finish(),
             Self::Cleanup(_) => f.debug_tuple("Cleanup").finish(),
+            Self::Tasks(arg0) => f.debug_tuple("Tasks").field(arg0).finish(),
         }
     }
 }
 
-pub struct ScopeDisposer(pub(crate) Box<dyn FnOnce()>);
+pub struct ScopeDisposer(pub(crate) BoxedCleanup);
 
 impl ScopeDisposer {
     pub fn dispose(self) {
@@ -167,17 +325,19 @@ impl ScopeDisposer {
 impl Scope {
     #[cfg(feature = "hydrate")]
     pub fn is_hydrating(&self) -> bool {
-        self.runtime.shared_context.borrow().is_some()
+        with_runtime(self.runtime, |runtime| {
+            runtime.shared_context.borrow().is_some()
+        })
     }
 
     #[cfg(feature = "hydrate")]
     pub fn start_hydration(&self, element: &web_sys::Element) {
-        self.runtime.start_hydration(element);
+        with_runtime(self.runtime, |runtime| runtime.start_hydration(element));
     }
 
     #[cfg(feature = "hydrate")]
     pub fn end_hydration(&self) {
-        self.runtime.end_hydration();
+        with_runtime(self.runtime, |runtime| runtime.end_hydration());
     }
 
     #[cfg(feature = "hydrate")]
@@ -197,27 +357,29 @@ impl Scope {
             t
         };
 
-        if let Some(ref mut shared_context) = &mut *self.runtime.shared_context.borrow_mut() {
-            if shared_context.context.is_some() {
-                let key = shared_context.next_hydration_key();
-                let node = shared_context.registry.remove(&key.to_string());
+        with_runtime(self.runtime, |runtime| {
+            if let Some(ref mut shared_context) = &mut *runtime.shared_context.borrow_mut() {
+                if shared_context.context.is_some() {
+                    let key = shared_context.next_hydration_key();
+                    let node = shared_context.registry.remove(&key.to_string());
 
-                //log::debug!("(hy) searching for {key}");
+                    //log::debug!("(hy) searching for {key}");
 
-                if let Some(node) = node {
-                    //log::debug!("(hy) found {key}");
-                    shared_context.completed.push(node.clone());
-                    node
+                    if let Some(node) = node {
+                        //log::debug!("(hy) found {key}");
+                        shared_context.completed.push(node.clone());
+                        node
+                    } else {
+                        //log::debug!("(hy) did NOT find {key}");
+                        cloned_template(template)
+                    }
                 } else {
-                    //log::debug!("(hy) did NOT find {key}");
                     cloned_template(template)
                 }
             } else {
                 cloned_template(template)
             }
-        } else {
-            cloned_template(template)
-        }
+        })
     }
 
     #[cfg(any(feature = "csr", feature = "hydrate"))]
@@ -227,14 +389,15 @@ impl Scope {
         let mut current = Vec::new();
         let mut start = start.clone();
 
-        if self
-            .runtime
-            .shared_context
-            .borrow()
-            .as_ref()
-            .map(|sc| sc.context.as_ref())
-            .is_some()
-        {
+        let is_hydrating = with_runtime(self.runtime, |runtime| {
+            runtime
+                .shared_context
+                .borrow()
+                .as_ref()
+                .map(|sc| sc.context.as_ref())
+                .is_some()
+        });
+        if is_hydrating {
             while let Some(curr) = end {
                 start = curr.clone();
                 if curr.node_type() == 8 {
@@ -259,28 +422,31 @@ impl Scope {
     }
 
     pub fn next_hydration_key(&self) -> String {
-        let mut sc = self.runtime.shared_context.borrow_mut();
-        if let Some(ref mut sc) = *sc {
-            sc.next_hydration_key()
-        } else {
-            let mut new_sc = SharedContext::default();
-            let id = new_sc.next_hydration_key();
-            *sc = Some(new_sc);
-            id
-        }
+        with_runtime(self.runtime, |runtime| {
+            let mut sc = runtime.shared_context.borrow_mut();
+            if let Some(ref mut sc) = *sc {
+                sc.next_hydration_key()
+            } else {
+                let mut new_sc = SharedContext::default();
+                let id = new_sc.next_hydration_key();
+                *sc = Some(new_sc);
+                id
+            }
+        })
     }
 
     pub fn with_next_context<T>(&self, f: impl FnOnce() -> T) -> T {
-        if self
-            .runtime
-            .shared_context
-            .borrow()
-            .as_ref()
-            .and_then(|sc| sc.context.as_ref())
-            .is_some()
-        {
-            let c = {
-                if let Some(ref mut sc) = *self.runtime.shared_context.borrow_mut() {
+        let has_context = with_runtime(self.runtime, |runtime| {
+            runtime
+                .shared_context
+                .borrow()
+                .as_ref()
+                .and_then(|sc| sc.context.as_ref())
+                .is_some()
+        });
+        if has_context {
+            let c = with_runtime(self.runtime, |runtime| {
+                if let Some(ref mut sc) = *runtime.shared_context.borrow_mut() {
                     if let Some(ref mut context) = sc.context {
                         let next = context.next_hydration_context();
                         Some(std::mem::replace(context, next))
@@ -290,13 +456,15 @@ impl Scope {
                 } else {
                     None
                 }
-            };
+            });
 
             let res = self.untrack(f);
 
-            if let Some(ref mut sc) = *self.runtime.shared_context.borrow_mut() {
-                sc.context = c;
-            }
+            with_runtime(self.runtime, |runtime| {
+                if let Some(ref mut sc) = *runtime.shared_context.borrow_mut() {
+                    sc.context = c;
+                }
+            });
             res
         } else {
             self.untrack(f)
@@ -305,27 +473,28 @@ impl Scope {
 
     /// Returns IDs for all [Resource](crate::Resource)s found on any scope.
     pub fn all_resources(&self) -> Vec<ResourceId> {
-        self.runtime.all_resources()
+        with_runtime(self.runtime, |runtime| runtime.all_resources())
     }
 
     /// Returns IDs for all [Resource](crate::Resource)s found on any scope.
     #[cfg(feature = "ssr")]
     pub fn serialization_resolvers(
         &self,
-    ) -> futures::stream::futures_unordered::FuturesUnordered<
-        std::pin::Pin<Box<dyn futures::Future<Output = (ResourceId, String)>>>,
-    > {
-        self.runtime.serialization_resolvers()
+    ) -> futures::stream::futures_unordered::FuturesUnordered<PinnedFuture<(ResourceId, String)>>
+    {
+        with_runtime(self.runtime, |runtime| runtime.serialization_resolvers())
     }
 
     #[cfg(feature = "ssr")]
     pub fn current_fragment_key(&self) -> String {
-        self.runtime
-            .shared_context
-            .borrow()
-            .as_ref()
-            .map(|context| context.current_fragment_key())
-            .unwrap_or_else(|| String::from("0f"))
+        with_runtime(self.runtime, |runtime| {
+            runtime
+                .shared_context
+                .borrow()
+                .as_ref()
+                .map(|context| context.current_fragment_key())
+                .unwrap_or_else(|| String::from("0f"))
+        })
     }
 
     #[cfg(feature = "ssr")]
@@ -333,12 +502,15 @@ impl Scope {
         &self,
         context: crate::SuspenseContext,
         key: &str,
-        resolver: impl FnOnce() -> String + 'static,
+        resolver: impl FnOnce() -> String + ThreadSafe + 'static,
     ) {
         use crate::{create_isomorphic_effect, SuspenseContext};
         use futures::{future::join_all, FutureExt, StreamExt};
 
-        if let Some(ref mut shared_context) = *self.runtime.shared_context.borrow_mut() {
+        let has_shared_context = with_runtime(self.runtime, |runtime| {
+            runtime.shared_context.borrow().is_some()
+        });
+        if has_shared_context {
             let (mut tx, mut rx) = futures::channel::mpsc::channel::<()>(1);
 
             create_isomorphic_effect(*self, move |fut| {
@@ -348,23 +520,29 @@ impl Scope {
                 }
             });
 
-            shared_context.pending_fragments.insert(
-                key.to_string(),
-                Box::pin(async move {
-                    rx.next().await;
-                    resolver()
-                }),
-            );
+            with_runtime(self.runtime, |runtime| {
+                if let Some(ref mut shared_context) = *runtime.shared_context.borrow_mut() {
+                    shared_context.pending_fragments.insert(
+                        key.to_string(),
+                        Box::pin(async move {
+                            rx.next().await;
+                            resolver()
+                        }),
+                    );
+                }
+            });
         }
     }
 
     #[cfg(feature = "ssr")]
-    pub fn pending_fragments(&self) -> HashMap<String, Pin<Box<dyn Future<Output = String>>>> {
-        if let Some(ref mut shared_context) = *self.runtime.shared_context.borrow_mut() {
-            std::mem::replace(&mut shared_context.pending_fragments, HashMap::new())
-        } else {
-            HashMap::new()
-        }
+    pub fn pending_fragments(&self) -> HashMap<String, PinnedFuture<String>> {
+        with_runtime(self.runtime, |runtime| {
+            if let Some(ref mut shared_context) = *runtime.shared_context.borrow_mut() {
+                std::mem::replace(&mut shared_context.pending_fragments, HashMap::new())
+            } else {
+                HashMap::new()
+            }
+        })
     }
 }
 
@@ -1,6 +1,10 @@
-use std::{cell::RefCell, collections::HashMap, fmt::Debug, hash::Hash, rc::Rc};
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
 
-use crate::{create_effect, create_signal, ReadSignal, Scope, WriteSignal};
+use crate::{
+    create_effect, create_signal,
+    sync::{RefCell, Shared},
+    untrack, ReadSignal, Scope, ThreadSafe, WriteSignal,
+};
 
 /// Creates a conditional signal that only notifies subscribers when a change
 /// in the source signal’s value changes whether it is equal to the key value
@@ -12,38 +16,38 @@ use crate::{create_effect, create_signal, ReadSignal, Scope, WriteSignal};
 ///
 /// ```
 /// # use leptos_reactive::{create_effect, create_scope, create_selector, create_signal};
-/// # use std::rc::Rc;
-/// # use std::cell::RefCell;
+/// # use std::sync::{Arc, Mutex};
 /// # create_scope(|cx| {
 ///    let (a, set_a) = create_signal(cx, 0);
 ///    let is_selected = create_selector(cx, a);
-///    let total_notifications = Rc::new(RefCell::new(0));
-///    let not = Rc::clone(&total_notifications);
+///    let total_notifications = Arc::new(Mutex::new(0));
+///    let not = Arc::clone(&total_notifications);
 ///    create_effect(cx, {let is_selected = is_selected.clone(); move |_| {
 ///      if is_selected(5) {
-///        *not.borrow_mut() += 1;
+///        *not.lock().unwrap() += 1;
 ///      }
 ///    }});
 ///
 ///    assert_eq!(is_selected(5), false);
-///    assert_eq!(*total_notifications.borrow(), 0);
+///    assert_eq!(*total_notifications.lock().unwrap(), 0);
 ///    set_a(5);
 ///    assert_eq!(is_selected(5), true);
-///    assert_eq!(*total_notifications.borrow(), 1);
+///    assert_eq!(*total_notifications.lock().unwrap(), 1);
 ///    set_a(5);
 ///    assert_eq!(is_selected(5), true);
-///    assert_eq!(*total_notifications.borrow(), 1);
+///    assert_eq!(*total_notifications.lock().unwrap(), 1);
 ///    set_a(4);
 ///    assert_eq!(is_selected(5), false);
 ///  # })
 ///  # .dispose()
 /// ```
+#[track_caller]
 pub fn create_selector<T>(
     cx: Scope,
-    source: impl Fn() -> T + Clone + 'static,
+    source: impl Fn() -> T + Clone + ThreadSafe + 'static,
 ) -> impl Fn(T) -> bool + Clone
 where
-    T: PartialEq + Eq + Debug + Clone + Hash + 'static,
+    T: PartialEq + Eq + Debug + Clone + Hash + ThreadSafe + 'static,
 {
     create_selector_with_fn(cx, source, |a, b| a == b)
 }
@@ -54,23 +58,24 @@ where
 /// **You probably don’t need this,** but it can be a very useful optimization
 /// in certain situations (e.g., “set the class `selected` if `selected() == this_row_index`)
 /// because it reduces them from `O(n)` to `O(1)`.
+#[track_caller]
 pub fn create_selector_with_fn<T>(
     cx: Scope,
-    source: impl Fn() -> T + Clone + 'static,
-    f: impl Fn(&T, &T) -> bool + Clone + 'static,
+    source: impl Fn() -> T + Clone + ThreadSafe + 'static,
+    f: impl Fn(&T, &T) -> bool + Clone + ThreadSafe + 'static,
 ) -> impl Fn(T) -> bool + Clone
 where
-    T: PartialEq + Eq + Debug + Clone + Hash + 'static,
+    T: PartialEq + Eq + Debug + Clone + Hash + ThreadSafe + 'static,
 {
     #[allow(clippy::type_complexity)]
-    let subs: Rc<RefCell<HashMap<T, (ReadSignal<bool>, WriteSignal<bool>)>>> =
-        Rc::new(RefCell::new(HashMap::new()));
-    let v = Rc::new(RefCell::new(None));
+    let subs: Shared<RefCell<HashMap<T, (ReadSignal<bool>, WriteSignal<bool>)>>> =
+        Shared::new(RefCell::new(HashMap::new()));
+    let v = Shared::new(RefCell::new(None));
 
     create_effect(cx, {
-        let subs = Rc::clone(&subs);
+        let subs = Shared::clone(&subs);
         let f = f.clone();
-        let v = Rc::clone(&v);
+        let v = Shared::clone(&v);
         move |prev: Option<T>| {
             let next_value = source();
             *v.borrow_mut() = Some(next_value.clone());
@@ -88,9 +93,10 @@ where
 
     move |key| {
         let mut subs = subs.borrow_mut();
+        // belongs to the selector, rather than to the effect that happens to read it first
         let (read, _) = subs
             .entry(key.clone())
-            .or_insert_with(|| create_signal(cx, false));
+            .or_insert_with(|| untrack(|| create_signal(cx, false)));
         _ = read();
         f(&key, v.borrow().as_ref().unwrap())
     }
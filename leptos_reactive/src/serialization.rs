@@ -0,0 +1,105 @@
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+use crate::ThreadSafe;
+
+/// Converts the value of a [Resource](crate::Resource) to and from the string that is
+/// sent from the server to the client, so that the client can hydrate the resource
+/// without loading it again.
+///
+/// Three formats are built in:
+/// - [Json], the default, which is readable but verbose.
+/// - [Bincode] (with the `bincode` feature), a compact binary format encoded as base64,
+///   which is much smaller for large numeric data.
+/// - [Miniserde] (with the `miniserde` feature), JSON through [miniserde], which
+///   compiles to less code than `serde_json`.
+///
+/// [create_resource](crate::create_resource) uses the [DefaultSerializer], which is
+/// [Bincode] if the `bincode` feature is enabled and [Json] otherwise. To choose the
+/// format of a single resource, use
+/// [create_resource_with_serializer](crate::create_resource_with_serializer).
+pub trait ResourceSerializer<T>: Copy + ThreadSafe + 'static {
+    fn serialize(&self, value: &T) -> Result<String, SerializationError>;
+
+    fn deserialize(&self, data: &str) -> Result<T, SerializationError>;
+}
+
+/// An error converting the value of a resource to or from a string.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SerializationError {
+    #[error("couldn't serialize resource: {0}")]
+    Serialize(String),
+    #[error("couldn't deserialize resource: {0}")]
+    Deserialize(String),
+}
+
+/// The [ResourceSerializer] used by [create_resource](crate::create_resource).
+#[cfg(not(feature = "bincode"))]
+pub type DefaultSerializer = Json;
+
+/// The [ResourceSerializer] used by [create_resource](crate::create_resource).
+#[cfg(feature = "bincode")]
+pub type DefaultSerializer = Bincode;
+
+/// Serializes resources as JSON, using `serde_json`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Json;
+
+impl<T> ResourceSerializer<T> for Json
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn serialize(&self, value: &T) -> Result<String, SerializationError> {
+        serde_json::to_string(value).map_err(|e| SerializationError::Serialize(e.to_string()))
+    }
+
+    fn deserialize(&self, data: &str) -> Result<T, SerializationError> {
+        serde_json::from_str(data).map_err(|e| SerializationError::Deserialize(e.to_string()))
+    }
+}
+
+/// Serializes resources with `bincode`, encoded as base64 so that they can be sent as
+/// a string.
+#[cfg(feature = "bincode")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl<T> ResourceSerializer<T> for Bincode
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn serialize(&self, value: &T) -> Result<String, SerializationError> {
+        let bytes =
+            bincode::serialize(value).map_err(|e| SerializationError::Serialize(e.to_string()))?;
+        Ok(base64::encode(bytes))
+    }
+
+    fn deserialize(&self, data: &str) -> Result<T, SerializationError> {
+        let bytes =
+            base64::decode(data).map_err(|e| SerializationError::Deserialize(e.to_string()))?;
+        bincode::deserialize(&bytes).map_err(|e| SerializationError::Deserialize(e.to_string()))
+    }
+}
+
+/// Serializes resources as JSON, using [miniserde].
+///
+/// Because miniserde has its own `Serialize` and `Deserialize` traits, this can only be
+/// chosen for individual resources, not as the [DefaultSerializer].
+#[cfg(feature = "miniserde")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Miniserde;
+
+#[cfg(feature = "miniserde")]
+impl<T> ResourceSerializer<T> for Miniserde
+where
+    T: miniserde::Serialize + miniserde::Deserialize,
+{
+    fn serialize(&self, value: &T) -> Result<String, SerializationError> {
+        Ok(miniserde::json::to_string(value))
+    }
+
+    fn deserialize(&self, data: &str) -> Result<T, SerializationError> {
+        miniserde::json::from_str(data).map_err(|e| SerializationError::Deserialize(e.to_string()))
+    }
+}
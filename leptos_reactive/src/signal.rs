@@ -1,6 +1,10 @@
-use crate::{Runtime, Scope, ScopeId, ScopeProperty};
+use crate::{
+    sync::Shared, try_with_runtime, untrack, with_runtime, Runtime, RuntimeId, Scope, ScopeId,
+    ScopeProperty, ThreadSafe,
+};
 use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, marker::PhantomData};
+use thiserror::Error;
 
 /// Creates a signal, the basic reactive primitive.
 ///
@@ -40,12 +44,65 @@ use std::{fmt::Debug, marker::PhantomData};
 /// # }).dispose();
 /// #
 /// ```
-pub fn create_signal<T>(cx: Scope, value: T) -> (ReadSignal<T>, WriteSignal<T>) {
+#[track_caller]
+pub fn create_signal<T>(cx: Scope, value: T) -> (ReadSignal<T>, WriteSignal<T>)
+where
+    T: ThreadSafe,
+{
     let s = cx.runtime.create_signal(value);
     cx.with_scope_property(|prop| prop.push(ScopeProperty::Signal(s.0.id)));
     s
 }
 
+/// Creates a signal, like [create_signal], that only notifies its subscribers when its
+/// value changes according to `eq`, which returns `true` if two values should be treated
+/// as the same.
+///
+/// Ordinary signals notify their subscribers every time they are updated, even if the new
+/// value is the same as the old one. The check runs whenever the signal is set, against
+/// the value it replaces; [WriteSignal::update] changes the value in place, so there is
+/// nothing to compare it with, and it always notifies.
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+/// # create_scope(|cx| {
+/// // a search box that ignores changes to leading or trailing whitespace
+/// let (query, set_query) =
+///   create_signal_with_eq(cx, String::new(), |a: &String, b: &String| a.trim() == b.trim());
+///
+/// let runs = Arc::new(AtomicUsize::new(0));
+/// create_isomorphic_effect(cx, {
+///   let runs = Arc::clone(&runs);
+///   move |_| {
+///     query();
+///     runs.fetch_add(1, Ordering::Relaxed);
+///   }
+/// });
+///
+/// set_query("leptos".to_string());
+/// set_query("leptos ".to_string());
+/// // the effect only re-ran for the first change
+/// assert_eq!(runs.load(Ordering::Relaxed), 2);
+/// assert_eq!(query(), "leptos ");
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_signal_with_eq<T>(
+    cx: Scope,
+    value: T,
+    eq: impl Fn(&T, &T) -> bool + ThreadSafe + 'static,
+) -> (ReadSignal<T>, WriteSignal<T>)
+where
+    T: Clone + ThreadSafe,
+{
+    let s = create_signal(cx, value);
+    let eq: SignalEq<T> = Shared::new(eq);
+    with_runtime(cx.runtime, |runtime| {
+        runtime.signal_eqs.borrow_mut().insert(s.0.id, Box::new(eq))
+    });
+    s
+}
+
 /// The getter for a reactive signal.
 ///
 /// A signal is a piece of data that may change over time,
@@ -94,7 +151,7 @@ pub struct ReadSignal<T>
 where
     T: 'static,
 {
-    pub(crate) runtime: &'static Runtime,
+    pub(crate) runtime: RuntimeId,
     pub(crate) id: SignalId,
     pub(crate) ty: PhantomData<T>,
 }
@@ -124,7 +181,7 @@ where
     /// });
     /// ```
     pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
-        self.id.with(self.runtime, f)
+        with_runtime(self.runtime, |runtime| self.id.with(runtime, f))
     }
 
     /// Clones and returns the current value of the signal, and subscribes
@@ -145,7 +202,68 @@ where
     where
         T: Clone,
     {
-        self.id.with(self.runtime, T::clone)
+        with_runtime(self.runtime, |runtime| self.id.with(runtime, T::clone))
+    }
+
+    /// Applies a function to the current value of the signal, without subscribing the
+    /// running effect to it. See [untrack](crate::untrack).
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+    /// # create_scope(|cx| {
+    /// let (name, set_name) = create_signal(cx, "Alice".to_string());
+    ///
+    /// let runs = Arc::new(AtomicUsize::new(0));
+    /// create_isomorphic_effect(cx, {
+    ///   let runs = Arc::clone(&runs);
+    ///   move |_| {
+    ///     name.with_untracked(|name| name.len());
+    ///     runs.fetch_add(1, Ordering::Relaxed);
+    ///   }
+    /// });
+    ///
+    /// // the effect didn't subscribe, so it doesn't re-run
+    /// set_name("Bob".to_string());
+    /// assert_eq!(runs.load(Ordering::Relaxed), 1);
+    /// # }).dispose();
+    /// ```
+    pub fn with_untracked<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        untrack(|| self.with(f))
+    }
+
+    /// Clones and returns the current value of the signal, without subscribing the
+    /// running effect to it. See [untrack](crate::untrack).
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with_untracked(T::clone)
+    }
+
+    /// Like [ReadSignal::with], but returns an error rather than panicking if the signal
+    /// has been disposed.
+    /// ```
+    /// # use leptos_reactive::*;
+    /// let (name, disposer) = run_scope_undisposed(|cx| {
+    ///   let (name, _) = create_signal(cx, "Alice".to_string());
+    ///   name
+    /// });
+    /// assert_eq!(name.try_with(|n| n.len()), Ok(5));
+    ///
+    /// disposer.dispose();
+    /// assert!(name.try_with(|n| n.len()).is_err());
+    /// ```
+    pub fn try_with<U>(&self, f: impl FnOnce(&T) -> U) -> Result<U, SignalError> {
+        try_with_runtime(self.runtime, |runtime| self.id.try_with(runtime, f))?
+    }
+
+    /// Like [ReadSignal::get], but returns an error rather than panicking if the signal
+    /// has been disposed.
+    pub fn try_get(&self) -> Result<T, SignalError>
+    where
+        T: Clone,
+    {
+        self.try_with(T::clone)
     }
 }
 
@@ -228,7 +346,7 @@ pub struct WriteSignal<T>
 where
     T: 'static,
 {
-    pub(crate) runtime: &'static Runtime,
+    pub(crate) runtime: RuntimeId,
     pub(crate) id: SignalId,
     pub(crate) ty: PhantomData<T>,
 }
@@ -258,7 +376,63 @@ where
     /// # }).dispose();
     /// ```
     pub fn update(&self, f: impl FnOnce(&mut T)) {
-        self.id.update(self.runtime, f)
+        with_runtime(self.runtime, |runtime| self.id.update(runtime, f))
+    }
+
+    /// Like [WriteSignal::update], but returns an error rather than panicking if the signal
+    /// has been disposed.
+    /// ```
+    /// # use leptos_reactive::*;
+    /// let (set_count, disposer) = run_scope_undisposed(|cx| {
+    ///   let (_, set_count) = create_signal(cx, 0);
+    ///   set_count
+    /// });
+    /// assert_eq!(set_count.try_update(|n| *n += 1), Ok(()));
+    ///
+    /// // a task that finishes after the scope has been disposed can ignore the error
+    /// disposer.dispose();
+    /// assert!(set_count.try_update(|n| *n += 1).is_err());
+    /// ```
+    pub fn try_update(&self, f: impl FnOnce(&mut T)) -> Result<(), SignalError> {
+        try_with_runtime(self.runtime, |runtime| self.id.try_update(runtime, f))?
+    }
+
+    /// Sets the value, returning an error rather than panicking if the signal has been disposed.
+    pub fn try_set(&self, value: T) -> Result<(), SignalError> {
+        try_with_runtime(self.runtime, |runtime| self.id.try_set(runtime, value))?
+    }
+
+    /// Sets the value, but only notifies subscribers if it is different from the current value.
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+    /// # create_scope(|cx| {
+    /// let (count, set_count) = create_signal(cx, 0);
+    ///
+    /// let runs = Arc::new(AtomicUsize::new(0));
+    /// create_isomorphic_effect(cx, {
+    ///   let runs = Arc::clone(&runs);
+    ///   move |_| {
+    ///     count();
+    ///     runs.fetch_add(1, Ordering::Relaxed);
+    ///   }
+    /// });
+    ///
+    /// // the value hasn't changed, so the effect doesn't re-run
+    /// set_count.set_if_changed(0);
+    /// assert_eq!(runs.load(Ordering::Relaxed), 1);
+    ///
+    /// set_count.set_if_changed(1);
+    /// assert_eq!(runs.load(Ordering::Relaxed), 2);
+    /// # }).dispose();
+    /// ```
+    pub fn set_if_changed(&self, value: T)
+    where
+        T: PartialEq,
+    {
+        with_runtime(self.runtime, |runtime| {
+            self.id.update_if(runtime, |n| set_if_changed(n, value))
+        })
     }
 }
 
@@ -284,7 +458,7 @@ where
     type Output = ();
 
     extern "rust-call" fn call_once(self, args: (T,)) -> Self::Output {
-        self.update(move |n| *n = args.0)
+        with_runtime(self.runtime, |runtime| self.id.set(runtime, args.0))
     }
 }
 
@@ -293,7 +467,7 @@ where
     T: Clone + 'static,
 {
     extern "rust-call" fn call_mut(&mut self, args: (T,)) -> Self::Output {
-        self.update(move |n| *n = args.0)
+        with_runtime(self.runtime, |runtime| self.id.set(runtime, args.0))
     }
 }
 
@@ -302,7 +476,7 @@ where
     T: Clone + 'static,
 {
     extern "rust-call" fn call(&self, args: (T,)) -> Self::Output {
-        self.update(move |n| *n = args.0)
+        with_runtime(self.runtime, |runtime| self.id.set(runtime, args.0))
     }
 }
 
@@ -327,7 +501,11 @@ where
 /// # }).dispose();
 /// #
 /// ```
-pub fn create_rw_signal<T>(cx: Scope, value: T) -> RwSignal<T> {
+#[track_caller]
+pub fn create_rw_signal<T>(cx: Scope, value: T) -> RwSignal<T>
+where
+    T: ThreadSafe,
+{
     let s = cx.runtime.create_rw_signal(value);
     cx.with_scope_property(|prop| prop.push(ScopeProperty::Signal(s.id)));
     s
@@ -359,7 +537,7 @@ pub struct RwSignal<T>
 where
     T: 'static,
 {
-    pub(crate) runtime: &'static Runtime,
+    pub(crate) runtime: RuntimeId,
     pub(crate) id: SignalId,
     pub(crate) ty: PhantomData<T>,
 }
@@ -369,22 +547,84 @@ where
     T: 'static,
 {
     pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
-        self.id.with(self.runtime, f)
+        with_runtime(self.runtime, |runtime| self.id.with(runtime, f))
     }
 
     pub fn get(&self) -> T
     where
         T: Clone,
     {
-        self.id.with(self.runtime, T::clone)
+        with_runtime(self.runtime, |runtime| self.id.with(runtime, T::clone))
     }
 
     pub fn update(&self, f: impl FnOnce(&mut T)) {
-        self.id.update(self.runtime, f)
+        with_runtime(self.runtime, |runtime| self.id.update(runtime, f))
     }
 
     pub fn set(&self, value: T) {
-        self.id.update(self.runtime, |n| *n = value)
+        with_runtime(self.runtime, |runtime| self.id.set(runtime, value))
+    }
+
+    /// Applies a function to the current value of the signal, without subscribing the
+    /// running effect to it. See [untrack](crate::untrack).
+    pub fn with_untracked<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        untrack(|| self.with(f))
+    }
+
+    /// Clones and returns the current value of the signal, without subscribing the
+    /// running effect to it. See [untrack](crate::untrack).
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with_untracked(T::clone)
+    }
+
+    /// Like [RwSignal::with], but returns an error rather than panicking if the signal
+    /// has been disposed.
+    pub fn try_with<U>(&self, f: impl FnOnce(&T) -> U) -> Result<U, SignalError> {
+        try_with_runtime(self.runtime, |runtime| self.id.try_with(runtime, f))?
+    }
+
+    /// Like [RwSignal::get], but returns an error rather than panicking if the signal
+    /// has been disposed.
+    pub fn try_get(&self) -> Result<T, SignalError>
+    where
+        T: Clone,
+    {
+        self.try_with(T::clone)
+    }
+
+    /// Like [RwSignal::update], but returns an error rather than panicking if the signal
+    /// has been disposed.
+    pub fn try_update(&self, f: impl FnOnce(&mut T)) -> Result<(), SignalError> {
+        try_with_runtime(self.runtime, |runtime| self.id.try_update(runtime, f))?
+    }
+
+    /// Like [RwSignal::set], but returns an error rather than panicking if the signal
+    /// has been disposed.
+    pub fn try_set(&self, value: T) -> Result<(), SignalError> {
+        try_with_runtime(self.runtime, |runtime| self.id.try_set(runtime, value))?
+    }
+
+    /// Sets the value, but only notifies subscribers if it is different from the current value.
+    pub fn set_if_changed(&self, value: T)
+    where
+        T: PartialEq,
+    {
+        with_runtime(self.runtime, |runtime| {
+            self.id.update_if(runtime, |n| set_if_changed(n, value))
+        })
+    }
+}
+
+/// Replaces the value if the new one is different, returning whether it did.
+fn set_if_changed<T: PartialEq>(current: &mut T, value: T) -> bool {
+    if *current == value {
+        false
+    } else {
+        *current = value;
+        true
     }
 }
 
@@ -417,29 +657,76 @@ where
     }
 }
 
+/// The error returned by the `try_` methods of signals and resources, like
+/// [ReadSignal::try_get], when the value they refer to no longer exists.
+///
+/// This usually happens when an `async` task finishes after the scope that owned the signal
+/// has been disposed, for example because the user has navigated away from a route.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalError {
+    #[error("tried to access a reactive runtime that has been disposed")]
+    RuntimeDisposed,
+    #[error("tried to access a signal that has been disposed: {0:?}")]
+    Disposed(SignalId),
+    #[cfg(feature = "resource")]
+    #[error("tried to access a resource that has been disposed: {0:?}")]
+    ResourceDisposed(crate::ResourceId),
+}
+
 // Internals
 slotmap::new_key_type! { pub struct SignalId; }
 
+/// Checks whether a signal’s current value and the one it is being set to are the same.
+/// See [create_signal_with_eq].
+#[cfg(not(feature = "multithreaded"))]
+pub(crate) type SignalEq<T> = Shared<dyn Fn(&T, &T) -> bool>;
+
+#[cfg(feature = "multithreaded")]
+pub(crate) type SignalEq<T> = Shared<dyn Fn(&T, &T) -> bool + Send + Sync>;
+
 impl SignalId {
     pub(crate) fn with<T, U>(&self, runtime: &Runtime, f: impl FnOnce(&T) -> U) -> U
     where
         T: 'static,
     {
+        self.try_with(runtime, f).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub(crate) fn try_with<T, U>(
+        &self,
+        runtime: &Runtime,
+        f: impl FnOnce(&T) -> U,
+    ) -> Result<U, SignalError>
+    where
+        T: 'static,
+    {
+        // if this signal holds a memo’s value, make sure it’s up to date
+        let memo = runtime.memos.borrow().get(*self).copied();
+        if let Some(memo) = memo {
+            runtime.update_if_necessary(memo);
+        }
+
+        let value = {
+            let signals = runtime.signals.borrow();
+            signals
+                .get(*self)
+                .cloned()
+                .ok_or(SignalError::Disposed(*self))?
+        };
+
         // add subscriber
-        if let Some(observer) = runtime.observer.get() {
+        if let Some(observer) = runtime.tracking_observer() {
             let mut subs = runtime.signal_subscribers.borrow_mut();
             if let Some(subs) = subs.entry(*self) {
                 subs.or_default().borrow_mut().insert(observer);
             }
+            let mut sources = runtime.effect_sources.borrow_mut();
+            if let Some(sources) = sources.entry(observer) {
+                sources.or_default().borrow_mut().insert(*self);
+            }
         }
 
         // get the value
-        let value = {
-            let signals = runtime.signals.borrow();
-            signals.get(*self).cloned().unwrap_or_else(|| {
-                panic!("tried to access a signal that has been disposed: {self:?}")
-            })
-        };
         let value = value.borrow();
         let value = value.downcast_ref::<T>().unwrap_or_else(|| {
             panic!(
@@ -448,48 +735,100 @@ impl SignalId {
                 std::any::type_name::<T>()
             )
         });
-        f(value)
+        Ok(f(value))
     }
 
     pub(crate) fn update<T>(&self, runtime: &Runtime, f: impl FnOnce(&mut T))
+    where
+        T: 'static,
+    {
+        self.try_update(runtime, f)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub(crate) fn try_update<T>(
+        &self,
+        runtime: &Runtime,
+        f: impl FnOnce(&mut T),
+    ) -> Result<(), SignalError>
+    where
+        T: 'static,
+    {
+        self.try_update_if(runtime, |value| {
+            f(value);
+            true
+        })
+    }
+
+    pub(crate) fn set<T>(&self, runtime: &Runtime, value: T)
+    where
+        T: 'static,
+    {
+        self.try_set(runtime, value)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Replaces the value, and notifies subscribers unless the signal was created with
+    /// [create_signal_with_eq] and the new value is the same as the old one.
+    pub(crate) fn try_set<T>(&self, runtime: &Runtime, value: T) -> Result<(), SignalError>
+    where
+        T: 'static,
+    {
+        let eq = runtime
+            .signal_eqs
+            .borrow()
+            .get(*self)
+            .and_then(|eq| eq.downcast_ref::<SignalEq<T>>())
+            .cloned();
+        self.try_update_if(runtime, |current| {
+            // compared before it's replaced, so the old value never has to be cloned
+            let changed = eq.is_none_or(|eq| !eq(current, &value));
+            *current = value;
+            changed
+        })
+    }
+
+    pub(crate) fn update_if<T>(&self, runtime: &Runtime, f: impl FnOnce(&mut T) -> bool)
+    where
+        T: 'static,
+    {
+        self.try_update_if(runtime, f)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Updates the value, and notifies subscribers if `f` returns `true`.
+    pub(crate) fn try_update_if<T>(
+        &self,
+        runtime: &Runtime,
+        f: impl FnOnce(&mut T) -> bool,
+    ) -> Result<(), SignalError>
     where
         T: 'static,
     {
         // update the value
-        {
+        let changed = {
             let value = {
                 let signals = runtime.signals.borrow();
-                signals.get(*self).cloned().unwrap_or_else(|| {
-                    panic!("tried to access a signal that has been disposed: {self:?}")
-                })
+                signals
+                    .get(*self)
+                    .cloned()
+                    .ok_or(SignalError::Disposed(*self))?
             };
             let mut value = value.borrow_mut();
-            let value = value.downcast_mut::<T>().unwrap_or_else(|| {
+            f(value.downcast_mut::<T>().unwrap_or_else(|| {
                 panic!(
                     "error casting signal {:?} to type {:?}",
                     self,
                     std::any::type_name::<T>()
                 )
-            });
-            f(value);
-        }
+            }))
+        };
 
         // notify subscribers
-        let subs = {
-            let subs = runtime.signal_subscribers.borrow();
-            let subs = subs.get(*self);
-            subs.map(|subs| subs.borrow().clone())
-        };
-        if let Some(subs) = subs {
-            for sub in subs {
-                let effect = {
-                    let effects = runtime.effects.borrow();
-                    effects.get(sub).cloned()
-                };
-                if let Some(effect) = effect {
-                    effect.borrow_mut().run(sub, runtime);
-                }
-            }
+        if changed {
+            runtime.mark_dirty(*self);
+            runtime.run_effects();
         }
+        Ok(())
     }
 }
@@ -1,5 +1,14 @@
 use std::future::Future;
 
+use futures::future::{abortable, AbortHandle};
+
+use crate::{
+    executor::runtime_executor,
+    runtime::RuntimeId,
+    sync::{Cell, Shared},
+    MaybeSend, Scope, ScopeProperty,
+};
+
 /// Exposes the [queueMicrotask](https://developer.mozilla.org/en-US/docs/Web/API/queueMicrotask) method
 /// in the browser, and simply runs the given function when on the server.
 #[cfg(not(target_arch = "wasm32"))]
@@ -43,7 +52,46 @@ pub fn spawn_local<F>(fut: F)
 where
     F: Future<Output = ()> + 'static,
 {
-    futures::executor::block_on(fut)
+    // without an async runtime, each task runs to completion with `block_on`. That can't
+    // be nested, so a task that spawns another (like an action whose result makes a
+    // resource refetch) would panic. Instead, tasks spawned while one is running are
+    // queued, and run in order once it's done.
+    use std::{cell::RefCell, collections::VecDeque, pin::Pin};
+
+    type LocalTask = Pin<Box<dyn Future<Output = ()>>>;
+
+    thread_local! {
+        // tasks spawned by the task that is currently running, which can't block on them
+        static QUEUED: RefCell<Option<VecDeque<LocalTask>>> = RefCell::new(None);
+    }
+
+    // stops queueing tasks once the outermost one is done, even if it panicked
+    struct Running;
+
+    impl Drop for Running {
+        fn drop(&mut self) {
+            QUEUED.with(|queued| queued.borrow_mut().take());
+        }
+    }
+
+    let nested = QUEUED.with(|queued| queued.borrow().is_some());
+    if nested {
+        QUEUED.with(|queued| {
+            if let Some(queued) = queued.borrow_mut().as_mut() {
+                queued.push_back(Box::pin(fut));
+            }
+        });
+        return;
+    }
+
+    QUEUED.with(|queued| *queued.borrow_mut() = Some(VecDeque::new()));
+    let _running = Running;
+    futures::executor::block_on(fut);
+    while let Some(task) =
+        QUEUED.with(|queued| queued.borrow_mut().as_mut().and_then(VecDeque::pop_front))
+    {
+        futures::executor::block_on(task);
+    }
 }
 
 #[cfg(any(test, doctest))]
@@ -53,3 +101,146 @@ where
 {
     tokio_test::block_on(fut);
 }
+
+/// Spawns a task in the background on the runtime’s [Executor](crate::Executor), if one
+/// is installed. Otherwise, with the `multithreaded` and `ssr` features enabled, this uses
+/// [tokio::spawn], so the task can be moved between worker threads; without them, it is
+/// the same as [spawn_local].
+pub(crate) fn spawn_task<F>(runtime: RuntimeId, fut: F)
+where
+    F: Future<Output = ()> + MaybeSend + 'static,
+{
+    match runtime_executor(runtime) {
+        Some(executor) => executor.spawn_local(Box::pin(fut)),
+        None => spawn_default(fut),
+    }
+}
+
+#[cfg(all(feature = "multithreaded", feature = "ssr", not(any(test, doctest))))]
+fn spawn_default<F>(fut: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(fut);
+}
+
+#[cfg(not(all(feature = "multithreaded", feature = "ssr", not(any(test, doctest)))))]
+fn spawn_default<F>(fut: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    spawn_local(fut)
+}
+
+/// Queues a microtask on the runtime’s [Executor](crate::Executor), if one is installed,
+/// or with [queue_microtask] otherwise.
+pub(crate) fn queue_runtime_microtask(runtime: RuntimeId, task: impl FnOnce() + 'static) {
+    match runtime_executor(runtime) {
+        Some(executor) => executor.queue_microtask(Box::new(task)),
+        None => queue_microtask(task),
+    }
+}
+
+impl Scope {
+    /// Spawns a task that belongs to this scope, using the runtime’s
+    /// [Executor](crate::Executor) or [spawn_local]. Unlike a task spawned with
+    /// [spawn_local] directly, it is aborted when the scope is disposed, so an async loop
+    /// started by a component stops once the component is gone.
+    ///
+    /// The returned [TaskHandle] can abort the task earlier, or check whether it is done.
+    ///
+    /// # Panics
+    /// Without an executor installed, and without the `csr`, `hydrate` or `ssr` feature
+    /// to provide an async runtime, there is nothing to run the task in the background.
+    /// Rather than blocking until the task is done, which may be never, this panics.
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # create_scope(|cx| {
+    /// // tests can run tasks on a TestExecutor; apps run them on the browser’s event loop,
+    /// // or on tokio with the `ssr` feature
+    /// let executor = TestExecutor::new();
+    /// cx.set_executor(executor.clone());
+    ///
+    /// let (count, set_count) = create_signal(cx, 0);
+    /// let task = cx.spawn(async move {
+    ///   loop {
+    ///     // poll a server, read from a websocket...
+    ///     set_count.update(|n| *n += 1);
+    ///     # break;
+    ///   }
+    /// });
+    ///
+    /// executor.run_until_stalled();
+    /// assert!(task.is_finished());
+    /// assert_eq!(count(), 1);
+    /// # }).dispose();
+    /// ```
+    #[track_caller]
+    pub fn spawn(&self, fut: impl Future<Output = ()> + 'static) -> TaskHandle {
+        let executor = runtime_executor(self.runtime);
+        #[cfg(not(any(feature = "csr", feature = "hydrate", feature = "ssr")))]
+        if executor.is_none() {
+            panic!(
+                "Scope::spawn needs an executor to run the task in the background: install one \
+                 with Scope::set_executor, or enable the `csr`, `hydrate` or `ssr` feature"
+            );
+        }
+
+        let (fut, abort_handle) = abortable(fut);
+        let finished = Shared::new(Cell::new(false));
+        let handle = TaskHandle {
+            abort_handle,
+            finished: Shared::clone(&finished),
+        };
+
+        // a task spawned while an effect runs belongs to that run, like anything else
+        // created in the effect's scope, so it is aborted before the effect runs again
+        self.with_scope_property({
+            let handle = handle.clone();
+            |prop| {
+                let tasks = prop.iter_mut().find_map(|property| match property {
+                    ScopeProperty::Tasks(tasks) => Some(tasks),
+                    _ => None,
+                });
+                match tasks {
+                    // forget the tasks that are done, so a long-lived scope that keeps
+                    // spawning short tasks doesn't keep growing
+                    Some(tasks) => {
+                        tasks.retain(|task| !task.is_finished());
+                        tasks.push(handle);
+                    }
+                    None => prop.push(ScopeProperty::Tasks(vec![handle])),
+                }
+            }
+        });
+
+        let fut = async move {
+            _ = fut.await;
+            finished.set(true);
+        };
+        match executor {
+            Some(executor) => executor.spawn_local(Box::pin(fut)),
+            None => spawn_local(fut),
+        }
+        handle
+    }
+}
+
+/// A handle to a task spawned with [Scope::spawn].
+#[derive(Clone, Debug)]
+pub struct TaskHandle {
+    abort_handle: AbortHandle,
+    finished: Shared<Cell<bool>>,
+}
+
+impl TaskHandle {
+    /// Stops running the task, if it hasn't finished yet.
+    pub fn abort(&self) {
+        self.abort_handle.abort();
+    }
+
+    /// Whether the task has run to completion or been aborted.
+    pub fn is_finished(&self) -> bool {
+        self.finished.get() || self.abort_handle.is_aborted()
+    }
+}
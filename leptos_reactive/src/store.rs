@@ -0,0 +1,654 @@
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    create_signal,
+    sync::{RefCell, Shared},
+    untrack, ReadSignal, Scope, ScopeDisposer, ThreadSafe, WriteSignal,
+};
+
+/// Creates a [Store], which holds a single value but tracks changes to each of its fields
+/// separately.
+///
+/// Where an [RwSignal](crate::RwSignal) holding a large struct notifies everything that
+/// reads it whenever any part of it changes, a store only notifies the readers of the
+/// field that changed (and of the fields that contain it). Use `#[derive(Store)]` on a
+/// struct to access its fields: `store.user().name()` reads and writes only `name`.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+/// # create_scope(|cx| {
+/// // `#[derive(Store)]` generates field accessors like this one
+/// trait CounterFields: StoreField<Value = (i32, i32)> {
+///   fn left(&self) -> Subfield<Self, i32> {
+///     Subfield::new(self.clone(), 0, |v| &v.0, |v| &mut v.0)
+///   }
+///
+///   fn right(&self) -> Subfield<Self, i32> {
+///     Subfield::new(self.clone(), 1, |v| &v.1, |v| &mut v.1)
+///   }
+/// }
+/// impl<F: StoreField<Value = (i32, i32)>> CounterFields for F {}
+///
+/// let store = create_store(cx, (0, 0));
+/// let runs = Arc::new(AtomicUsize::new(0));
+/// create_effect(cx, {
+///   let store = store.clone();
+///   let runs = Arc::clone(&runs);
+///   move |_| {
+///     store.left().get();
+///     runs.fetch_add(1, Ordering::Relaxed);
+///   }
+/// });
+///
+/// // changing the field notifies its readers...
+/// store.left().set(1);
+/// assert_eq!(runs.load(Ordering::Relaxed), 2);
+///
+/// // ...but changing another field doesn't
+/// store.right().set(1);
+/// assert_eq!(runs.load(Ordering::Relaxed), 2);
+///
+/// // replacing the whole value changes every field
+/// store.set((2, 2));
+/// assert_eq!(runs.load(Ordering::Relaxed), 3);
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_store<T>(cx: Scope, value: T) -> Store<T>
+where
+    T: ThreadSafe + 'static,
+{
+    Store {
+        inner: Shared::new(StoreInner {
+            cx,
+            value: RefCell::new(value),
+            triggers: Default::default(),
+        }),
+    }
+}
+
+/// A value whose fields are tracked separately. See [create_store].
+pub struct Store<T>
+where
+    T: 'static,
+{
+    inner: Shared<StoreInner<T>>,
+}
+
+struct StoreInner<T> {
+    cx: Scope,
+    value: RefCell<T>,
+    /// A signal for each path that has been read, used to notify its readers, arranged
+    /// like the paths themselves so a change only visits the paths it affects.
+    triggers: RefCell<TriggerNode>,
+}
+
+/// The trigger for a path, if it has been read, and the triggers for the paths inside it.
+#[derive(Default)]
+struct TriggerNode {
+    trigger: Option<Trigger>,
+    children: HashMap<StorePathSegment, TriggerNode>,
+}
+
+struct Trigger {
+    read: ReadSignal<()>,
+    write: WriteSignal<()>,
+    /// Each trigger has a scope of its own, so it can be disposed once its path is gone.
+    disposer: ScopeDisposer,
+}
+
+impl TriggerNode {
+    fn get(&self, path: &StorePath) -> Option<&TriggerNode> {
+        path.0
+            .iter()
+            .try_fold(self, |node, segment| node.children.get(segment))
+    }
+
+    fn get_mut(&mut self, path: &StorePath) -> Option<&mut TriggerNode> {
+        path.0
+            .iter()
+            .try_fold(self, |node, segment| node.children.get_mut(segment))
+    }
+
+    /// Every trigger in this node and the nodes inside it.
+    fn collect(&self, triggers: &mut Vec<WriteSignal<()>>) {
+        triggers.extend(self.trigger.as_ref().map(|trigger| trigger.write));
+        for child in self.children.values() {
+            child.collect(triggers);
+        }
+    }
+
+    fn into_disposers(self, disposers: &mut Vec<ScopeDisposer>) {
+        disposers.extend(self.trigger.map(|trigger| trigger.disposer));
+        for child in self.children.into_values() {
+            child.into_disposers(disposers);
+        }
+    }
+}
+
+impl<T> Store<T>
+where
+    T: ThreadSafe + 'static,
+{
+    /// Subscribes the running effect to changes to the given path.
+    fn track(&self, path: &StorePath) {
+        let trigger = self
+            .inner
+            .triggers
+            .borrow()
+            .get(path)
+            .and_then(|node| node.trigger.as_ref())
+            .map(|trigger| trigger.read);
+        let trigger = trigger.unwrap_or_else(|| {
+            // belongs to the store, rather than to the effect that happens to read it first
+            let cx = self.inner.cx;
+            let ((read, write), disposer) = untrack(|| {
+                cx.runtime
+                    .run_scope_undisposed(|cx| create_signal(cx, ()), Some(cx))
+            });
+            let mut triggers = self.inner.triggers.borrow_mut();
+            let node = path.0.iter().fold(&mut *triggers, |node, segment| {
+                node.children.entry(segment.clone()).or_default()
+            });
+            node.trigger = Some(Trigger {
+                read,
+                write,
+                disposer,
+            });
+            read
+        });
+        trigger.with(|_| ());
+    }
+
+    /// Notifies the readers of the given path, of any path that contains it (since they
+    /// read its value as part of their own), and of any path inside it.
+    fn notify(&self, path: &StorePath) {
+        let mut triggers = Vec::new();
+        {
+            let root = self.inner.triggers.borrow();
+            let mut node = Some(&*root);
+            for segment in &path.0 {
+                let Some(parent) = node else { break };
+                triggers.extend(parent.trigger.as_ref().map(|trigger| trigger.write));
+                node = parent.children.get(segment);
+            }
+            if let Some(node) = node {
+                node.collect(&mut triggers);
+            }
+        }
+        self.inner.cx.batch(|| {
+            for trigger in triggers {
+                trigger.update(|_| {});
+            }
+        });
+    }
+
+    /// Disposes of the triggers for the items in the [Keyed] field at `path` whose keys
+    /// `keep` rejects, along with the triggers for the paths inside them.
+    fn remove_keys(&self, path: &StorePath, keep: impl Fn(&StoreKey) -> bool) {
+        let mut disposers = Vec::new();
+        if let Some(node) = self.inner.triggers.borrow_mut().get_mut(path) {
+            let removed = node
+                .children
+                .keys()
+                .filter(|segment| matches!(segment, StorePathSegment::Key(key) if !keep(key)))
+                .cloned()
+                .collect::<Vec<_>>();
+            for segment in removed {
+                if let Some(child) = node.children.remove(&segment) {
+                    child.into_disposers(&mut disposers);
+                }
+            }
+        }
+
+        // disposing runs cleanups, so it shouldn’t happen while the triggers are borrowed
+        for disposer in disposers {
+            disposer.dispose();
+        }
+    }
+}
+
+impl<T> Clone for Store<T>
+where
+    T: 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Shared::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Debug for Store<T>
+where
+    T: Debug + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Store")
+            .field("value", &self.inner.value)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A part of a [Store]: either the whole store, or a field within it.
+///
+/// Reading it with [with](StoreField::with) or [get](StoreField::get) subscribes the
+/// running effect to changes to this field, and writing it with
+/// [update](StoreField::update) or [set](StoreField::set) notifies only the readers of
+/// this field, the fields that contain it, and the fields inside it.
+pub trait StoreField: Clone + 'static {
+    /// The type of the value held by the whole store.
+    type Root: ThreadSafe + 'static;
+    /// The type of this field.
+    type Value: 'static;
+
+    #[doc(hidden)]
+    fn store(&self) -> &Store<Self::Root>;
+
+    #[doc(hidden)]
+    fn path(&self) -> StorePath;
+
+    /// Applies a function to the current value, without subscribing to changes.
+    fn with_untracked<U>(&self, f: impl FnOnce(&Self::Value) -> U) -> U;
+
+    #[doc(hidden)]
+    fn update_untracked<U>(&self, f: impl FnOnce(&mut Self::Value) -> U) -> U;
+
+    /// Applies a function to the current value, and subscribes the running effect to
+    /// changes to this field.
+    fn with<U>(&self, f: impl FnOnce(&Self::Value) -> U) -> U {
+        self.store().track(&self.path());
+        self.with_untracked(f)
+    }
+
+    /// Clones and returns the current value, and subscribes the running effect to
+    /// changes to this field.
+    fn get(&self) -> Self::Value
+    where
+        Self::Value: Clone,
+    {
+        self.with(Self::Value::clone)
+    }
+
+    /// Applies a function to the current value to mutate it in place, and notifies
+    /// the readers of this field.
+    fn update(&self, f: impl FnOnce(&mut Self::Value)) {
+        self.update_untracked(f);
+        self.store().notify(&self.path());
+    }
+
+    /// Sets the value, and notifies the readers of this field.
+    fn set(&self, value: Self::Value) {
+        self.update(|n| *n = value);
+    }
+}
+
+impl<T> StoreField for Store<T>
+where
+    T: ThreadSafe + 'static,
+{
+    type Root = T;
+    type Value = T;
+
+    fn store(&self) -> &Store<T> {
+        self
+    }
+
+    fn path(&self) -> StorePath {
+        StorePath::default()
+    }
+
+    fn with_untracked<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        f(&self.inner.value.borrow())
+    }
+
+    fn update_untracked<U>(&self, f: impl FnOnce(&mut T) -> U) -> U {
+        f(&mut self.inner.value.borrow_mut())
+    }
+}
+
+/// A field of a struct in a [Store], generated by `#[derive(Store)]`.
+pub struct Subfield<P, T>
+where
+    P: StoreField,
+{
+    parent: P,
+    index: usize,
+    read: fn(&P::Value) -> &T,
+    write: fn(&mut P::Value) -> &mut T,
+}
+
+impl<P, T> Subfield<P, T>
+where
+    P: StoreField,
+{
+    /// Creates a subfield with the given index (which must be unique within its parent)
+    /// and functions to access it within the parent’s value.
+    pub fn new(
+        parent: P,
+        index: usize,
+        read: fn(&P::Value) -> &T,
+        write: fn(&mut P::Value) -> &mut T,
+    ) -> Self {
+        Self {
+            parent,
+            index,
+            read,
+            write,
+        }
+    }
+}
+
+impl<P, T> Clone for Subfield<P, T>
+where
+    P: StoreField,
+{
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent.clone(),
+            index: self.index,
+            read: self.read,
+            write: self.write,
+        }
+    }
+}
+
+impl<P, T> StoreField for Subfield<P, T>
+where
+    P: StoreField,
+    T: 'static,
+{
+    type Root = P::Root;
+    type Value = T;
+
+    fn store(&self) -> &Store<P::Root> {
+        self.parent.store()
+    }
+
+    fn path(&self) -> StorePath {
+        self.parent
+            .path()
+            .child(StorePathSegment::Field(self.index))
+    }
+
+    fn with_untracked<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.parent.with_untracked(|value| f((self.read)(value)))
+    }
+
+    fn update_untracked<U>(&self, f: impl FnOnce(&mut T) -> U) -> U {
+        self.parent.update_untracked(|value| f((self.write)(value)))
+    }
+}
+
+/// A [Vec] field in a [Store] whose items are identified by a key, generated by
+/// `#[derive(Store)]` for fields marked `#[store(key: KeyType = |item| item.key)]`.
+///
+/// Each item is tracked separately, so changing one item doesn’t notify the readers of
+/// another. Reading the list of [keys](Keyed::keys) only subscribes to items being added,
+/// removed or reordered, not to changes inside them. Once an item is removed, the
+/// signals that tracked it are disposed the next time the list is updated or its keys
+/// are read.
+pub struct Keyed<P, T, K>
+where
+    P: StoreField<Value = Vec<T>>,
+{
+    inner: P,
+    key_fn: fn(&T) -> K,
+}
+
+impl<P, T, K> Keyed<P, T, K>
+where
+    P: StoreField<Value = Vec<T>>,
+    T: 'static,
+    K: Hash + Eq + Clone + 'static,
+{
+    pub fn new(inner: P, key_fn: fn(&T) -> K) -> Self {
+        Self { inner, key_fn }
+    }
+
+    /// The keys of the items, in order. Subscribes the running effect to changes to the
+    /// list of items, but not to changes within each item.
+    pub fn keys(&self) -> Vec<K> {
+        self.store()
+            .track(&self.path().child(StorePathSegment::Keys));
+        let keys: Vec<K> = self.with_untracked(|items| items.iter().map(self.key_fn).collect());
+        self.remove_stale_triggers(&keys);
+        keys
+    }
+
+    /// Disposes of the signals tracking items that are no longer in the list.
+    fn remove_stale_triggers(&self, keys: &[K]) {
+        let keys = keys.iter().collect::<HashSet<_>>();
+        self.store().remove_keys(&self.path(), |key| {
+            key.downcast_ref::<K>()
+                .is_some_and(|key| keys.contains(key))
+        });
+    }
+
+    /// The item with the given key.
+    ///
+    /// # Panics
+    /// Reading or writing the item panics if no item has this key.
+    pub fn at_key(&self, key: K) -> KeyedItem<P, T, K> {
+        KeyedItem {
+            parent: self.inner.clone(),
+            key,
+            key_fn: self.key_fn,
+        }
+    }
+
+    /// Each of the items, in order. See [Keyed::keys].
+    pub fn items(&self) -> Vec<KeyedItem<P, T, K>> {
+        self.keys()
+            .into_iter()
+            .map(|key| self.at_key(key))
+            .collect()
+    }
+}
+
+impl<P, T, K> Clone for Keyed<P, T, K>
+where
+    P: StoreField<Value = Vec<T>>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            key_fn: self.key_fn,
+        }
+    }
+}
+
+impl<P, T, K> StoreField for Keyed<P, T, K>
+where
+    P: StoreField<Value = Vec<T>>,
+    T: 'static,
+    K: Hash + Eq + Clone + 'static,
+{
+    type Root = P::Root;
+    type Value = Vec<T>;
+
+    fn store(&self) -> &Store<P::Root> {
+        self.inner.store()
+    }
+
+    fn path(&self) -> StorePath {
+        self.inner.path()
+    }
+
+    fn with_untracked<U>(&self, f: impl FnOnce(&Vec<T>) -> U) -> U {
+        self.inner.with_untracked(f)
+    }
+
+    fn update_untracked<U>(&self, f: impl FnOnce(&mut Vec<T>) -> U) -> U {
+        self.inner.update_untracked(f)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Vec<T>)) {
+        self.update_untracked(f);
+        self.store().notify(&self.path());
+        let keys = self.with_untracked(|items| items.iter().map(self.key_fn).collect::<Vec<_>>());
+        self.remove_stale_triggers(&keys);
+    }
+}
+
+/// An item in a [Keyed] field of a [Store].
+pub struct KeyedItem<P, T, K>
+where
+    P: StoreField<Value = Vec<T>>,
+{
+    parent: P,
+    key: K,
+    key_fn: fn(&T) -> K,
+}
+
+impl<P, T, K> KeyedItem<P, T, K>
+where
+    P: StoreField<Value = Vec<T>>,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<P, T, K> Clone for KeyedItem<P, T, K>
+where
+    P: StoreField<Value = Vec<T>>,
+    K: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent.clone(),
+            key: self.key.clone(),
+            key_fn: self.key_fn,
+        }
+    }
+}
+
+impl<P, T, K> StoreField for KeyedItem<P, T, K>
+where
+    P: StoreField<Value = Vec<T>>,
+    T: 'static,
+    K: Hash + Eq + Clone + Debug + ThreadSafe + 'static,
+{
+    type Root = P::Root;
+    type Value = T;
+
+    fn store(&self) -> &Store<P::Root> {
+        self.parent.store()
+    }
+
+    fn path(&self) -> StorePath {
+        self.parent
+            .path()
+            .child(StorePathSegment::Key(StoreKey(Shared::new(
+                self.key.clone(),
+            ))))
+    }
+
+    fn with_untracked<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.parent.with_untracked(|items| {
+            let item = items
+                .iter()
+                .find(|item| (self.key_fn)(item) == self.key)
+                .unwrap_or_else(|| panic!("no item in store with key {:?}", self.key));
+            f(item)
+        })
+    }
+
+    fn update_untracked<U>(&self, f: impl FnOnce(&mut T) -> U) -> U {
+        self.parent.update_untracked(|items| {
+            let item = items
+                .iter_mut()
+                .find(|item| (self.key_fn)(item) == self.key)
+                .unwrap_or_else(|| panic!("no item in store with key {:?}", self.key));
+            f(item)
+        })
+    }
+}
+
+/// The location of a field within a [Store].
+#[doc(hidden)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StorePath(Vec<StorePathSegment>);
+
+#[doc(hidden)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StorePathSegment {
+    /// A field of a struct, by index.
+    Field(usize),
+    /// An item in a [Keyed] field, by its key.
+    Key(StoreKey),
+    /// The list of keys in a [Keyed] field.
+    Keys,
+}
+
+/// The key of an item in a [Keyed] field, compared by value rather than by its hash, so
+/// two items whose keys hash the same are still tracked separately.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct StoreKey(Shared<dyn AnyKey>);
+
+impl StoreKey {
+    fn downcast_ref<K: 'static>(&self) -> Option<&K> {
+        self.0.as_any().downcast_ref()
+    }
+}
+
+impl PartialEq for StoreKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_key(&*other.0)
+    }
+}
+
+impl Eq for StoreKey {}
+
+impl Hash for StoreKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_key(state);
+    }
+}
+
+impl Debug for StoreKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+trait AnyKey: Debug + ThreadSafe {
+    fn as_any(&self) -> &dyn Any;
+
+    fn eq_key(&self, other: &dyn AnyKey) -> bool;
+
+    fn hash_key(&self, state: &mut dyn Hasher);
+}
+
+impl<K> AnyKey for K
+where
+    K: Hash + Eq + Debug + ThreadSafe + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_key(&self, other: &dyn AnyKey) -> bool {
+        other.as_any().downcast_ref::<K>() == Some(self)
+    }
+
+    fn hash_key(&self, mut state: &mut dyn Hasher) {
+        self.hash(&mut state);
+    }
+}
+
+impl StorePath {
+    fn child(&self, segment: StorePathSegment) -> Self {
+        let mut path = self.0.clone();
+        path.push(segment);
+        Self(path)
+    }
+}
@@ -0,0 +1,126 @@
+use std::marker::PhantomData;
+
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver},
+    future::ready,
+    stream::abortable,
+    Stream, StreamExt,
+};
+
+use crate::{
+    create_isomorphic_effect, create_signal, on_cleanup, spawn::spawn_task, MaybeSend, Memo,
+    ReadSignal, RwSignal, Scope, ThreadSafe,
+};
+
+/// Creates a signal that holds the latest value yielded by a [Stream], starting with
+/// `initial`, so that a websocket feed or a stream of server-sent events can be read
+/// like any other signal.
+///
+/// The stream is polled in the background, and stops being polled when `cx` is disposed.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// let messages = futures::stream::iter(vec!["hello", "world"]);
+/// let latest = create_signal_from_stream(cx, messages, "");
+///
+/// // (in this example, the stream is already finished)
+/// assert_eq!(latest(), "world");
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_signal_from_stream<T>(
+    cx: Scope,
+    stream: impl Stream<Item = T> + MaybeSend + 'static,
+    initial: T,
+) -> ReadSignal<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    let (value, set_value) = create_signal(cx, initial);
+
+    let (stream, abort_handle) = abortable(stream);
+    on_cleanup(cx, move || abort_handle.abort());
+
+    spawn_task(
+        cx.runtime,
+        stream.for_each(move |item| {
+            // the scope may have been disposed while the stream was waiting for an item
+            _ = set_value.try_update(|n| *n = item);
+            ready(())
+        }),
+    );
+
+    value
+}
+
+/// Sends each value of a signal into a channel, until `cx` is disposed.
+fn signal_to_stream<T>(
+    cx: Scope,
+    get: impl Fn() -> T + ThreadSafe + 'static,
+) -> UnboundedReceiver<T>
+where
+    T: ThreadSafe + 'static,
+{
+    let (tx, rx) = unbounded();
+    create_isomorphic_effect(cx, move |_| {
+        // the receiver may have been dropped, which is fine
+        _ = tx.unbounded_send(get());
+    });
+    rx
+}
+
+impl<T> ReadSignal<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    /// Returns a [Stream] that yields the current value of the signal, then each new value
+    /// as it changes. The stream ends when `cx` is disposed.
+    ///
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # use futures::StreamExt;
+    /// # create_scope(|cx| {
+    /// let (count, set_count) = create_signal(cx, 0);
+    /// let mut counts = count.to_stream(cx);
+    ///
+    /// set_count(1);
+    /// # futures::executor::block_on(async move {
+    /// assert_eq!(counts.next().await, Some(0));
+    /// assert_eq!(counts.next().await, Some(1));
+    /// # });
+    /// # }).dispose();
+    /// ```
+    pub fn to_stream(&self, cx: Scope) -> impl Stream<Item = T> {
+        let signal = *self;
+        signal_to_stream(cx, move || signal.get())
+    }
+}
+
+impl<T> RwSignal<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    /// Returns a [Stream] that yields the current value of the signal, then each new value
+    /// as it changes. The stream ends when `cx` is disposed. See [ReadSignal::to_stream].
+    pub fn to_stream(&self, cx: Scope) -> impl Stream<Item = T> {
+        let signal = ReadSignal {
+            runtime: self.runtime,
+            id: self.id,
+            ty: PhantomData,
+        };
+        signal.to_stream(cx)
+    }
+}
+
+impl<T> Memo<T>
+where
+    T: Clone + ThreadSafe + 'static,
+{
+    /// Returns a [Stream] that yields the current value of the memo, then each new value
+    /// as it changes. The stream ends when `cx` is disposed. See [ReadSignal::to_stream].
+    pub fn to_stream(&self, cx: Scope) -> impl Stream<Item = T> {
+        let memo = *self;
+        signal_to_stream(cx, move || memo.get())
+    }
+}
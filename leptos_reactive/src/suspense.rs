@@ -1,12 +1,17 @@
 #[cfg(feature = "ssr")]
 use std::{cell::RefCell, future::Future, pin::Pin, rc::Rc};
 
-use crate::{create_signal, spawn::queue_microtask, ReadSignal, Scope, WriteSignal};
+use crate::{create_signal, spawn::queue_runtime_microtask, ReadSignal, Scope, WriteSignal};
 
 #[derive(Copy, Clone, Debug)]
 pub struct SuspenseContext {
     pub pending_resources: ReadSignal<usize>,
     set_pending_resources: WriteSignal<usize>,
+    /// How many of the pending resources are loading as part of a [Transition](crate::Transition).
+    #[cfg(feature = "transition")]
+    pending_transition_resources: ReadSignal<usize>,
+    #[cfg(feature = "transition")]
+    set_pending_transition_resources: WriteSignal<usize>,
 }
 
 impl std::hash::Hash for SuspenseContext {
@@ -26,20 +31,28 @@ impl Eq for SuspenseContext {}
 impl SuspenseContext {
     pub fn new(cx: Scope) -> Self {
         let (pending_resources, set_pending_resources) = create_signal(cx, 0);
+        #[cfg(feature = "transition")]
+        let (pending_transition_resources, set_pending_transition_resources) = create_signal(cx, 0);
         Self {
             pending_resources,
             set_pending_resources,
+            #[cfg(feature = "transition")]
+            pending_transition_resources,
+            #[cfg(feature = "transition")]
+            set_pending_transition_resources,
         }
     }
 
     pub fn increment(&self) {
         let setter = self.set_pending_resources;
-        queue_microtask(move || setter.update(|n| *n += 1));
+        queue_runtime_microtask(self.pending_resources.runtime, move || {
+            setter.update(|n| *n += 1)
+        });
     }
 
     pub fn decrement(&self) {
         let setter = self.set_pending_resources;
-        queue_microtask(move || {
+        queue_runtime_microtask(self.pending_resources.runtime, move || {
             setter.update(|n| {
                 if *n > 0 {
                     *n -= 1
@@ -51,4 +64,40 @@ impl SuspenseContext {
     pub fn ready(&self) -> bool {
         self.pending_resources.get() == 0
     }
+
+    /// Like [increment](Self::increment), for a resource that started loading as part
+    /// of a [Transition](crate::Transition).
+    #[cfg(feature = "transition")]
+    pub(crate) fn increment_transition(&self) {
+        let setter = self.set_pending_resources;
+        let transition_setter = self.set_pending_transition_resources;
+        queue_runtime_microtask(self.pending_resources.runtime, move || {
+            // counted as part of the transition first, so that it never looks like
+            // a resource that would show the fallback
+            transition_setter.update(|n| *n += 1);
+            setter.update(|n| *n += 1);
+        });
+    }
+
+    /// Like [decrement](Self::decrement), for a resource that was loading as part of
+    /// a [Transition](crate::Transition).
+    #[cfg(feature = "transition")]
+    pub(crate) fn decrement_transition(&self) {
+        let setter = self.set_pending_resources;
+        let transition_setter = self.set_pending_transition_resources;
+        queue_runtime_microtask(self.pending_resources.runtime, move || {
+            setter.update(|n| *n = n.saturating_sub(1));
+            transition_setter.update(|n| *n = n.saturating_sub(1));
+        });
+    }
+
+    /// Whether every resource this is waiting for is loading as part of a
+    /// [Transition](crate::Transition), so `<Suspense/>` should keep showing its
+    /// children rather than its fallback. Resources that load outside a transition,
+    /// or under another `<Suspense/>`, don't count.
+    #[cfg(feature = "transition")]
+    pub fn transition_pending(&self) -> bool {
+        let pending = self.pending_resources.get();
+        pending > 0 && self.pending_transition_resources.get() >= pending
+    }
 }
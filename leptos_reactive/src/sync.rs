@@ -0,0 +1,273 @@
+//! Shared-ownership and interior-mutability types used throughout the reactive system.
+//!
+//! By default these are the single-threaded [std::rc::Rc], [std::cell::Cell] and
+//! [std::cell::RefCell]. With the `multithreaded` feature they are replaced by
+//! [std::sync::Arc] and lock-based cells with the same API, which makes the runtime,
+//! signals and resources `Send + Sync`. Each runtime is still only used by one thread at
+//! a time: see [with_runtime](crate::runtime::with_runtime).
+
+use std::{future::Future, pin::Pin};
+
+#[cfg(not(feature = "multithreaded"))]
+pub(crate) use std::{
+    cell::{Cell, RefCell},
+    rc::Rc as Shared,
+};
+
+#[cfg(feature = "multithreaded")]
+pub(crate) use std::sync::Arc as Shared;
+#[cfg(feature = "multithreaded")]
+pub(crate) use threadsafe::{Cell, RefCell};
+
+/// A value that can be stored in the reactive system.
+///
+/// With the `multithreaded` feature enabled, this requires [Send] and [Sync], so that
+/// signals, effects, and resources can be shared between threads. Otherwise, it is
+/// implemented for every type.
+#[cfg(not(feature = "multithreaded"))]
+pub trait ThreadSafe {}
+#[cfg(not(feature = "multithreaded"))]
+impl<T: ?Sized> ThreadSafe for T {}
+
+/// A value that can be stored in the reactive system.
+///
+/// With the `multithreaded` feature enabled, this requires [Send] and [Sync], so that
+/// signals, effects, and resources can be shared between threads. Otherwise, it is
+/// implemented for every type.
+#[cfg(feature = "multithreaded")]
+pub trait ThreadSafe: Send + Sync {}
+#[cfg(feature = "multithreaded")]
+impl<T: Send + Sync + ?Sized> ThreadSafe for T {}
+
+/// A value, usually a [Future], that may need to be moved to another thread.
+///
+/// With the `multithreaded` feature enabled, this requires [Send]. Otherwise, it is
+/// implemented for every type.
+#[cfg(not(feature = "multithreaded"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "multithreaded"))]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// A value, usually a [Future], that may need to be moved to another thread.
+///
+/// With the `multithreaded` feature enabled, this requires [Send]. Otherwise, it is
+/// implemented for every type.
+#[cfg(feature = "multithreaded")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "multithreaded")]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+/// A type-erased value held by a signal or context, which is `Send + Sync` if the
+/// `multithreaded` feature is enabled.
+#[cfg(not(feature = "multithreaded"))]
+pub(crate) type AnyValue = dyn std::any::Any;
+
+/// A type-erased value held by a signal or context, which is `Send + Sync` if the
+/// `multithreaded` feature is enabled.
+#[cfg(feature = "multithreaded")]
+pub(crate) type AnyValue = dyn std::any::Any + Send + Sync;
+
+/// A boxed [Future], which is [Send] if the `multithreaded` feature is enabled.
+#[cfg(not(feature = "multithreaded"))]
+pub type PinnedFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// A boxed [Future], which is [Send] if the `multithreaded` feature is enabled.
+#[cfg(feature = "multithreaded")]
+pub type PinnedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// A boxed cleanup function, which is `Send + Sync` if the `multithreaded` feature is enabled.
+#[cfg(not(feature = "multithreaded"))]
+pub(crate) type BoxedCleanup = Box<dyn FnOnce()>;
+
+/// A boxed cleanup function, which is `Send + Sync` if the `multithreaded` feature is enabled.
+#[cfg(feature = "multithreaded")]
+pub(crate) type BoxedCleanup = Box<dyn FnOnce() + Send + Sync>;
+
+#[cfg(feature = "multithreaded")]
+mod threadsafe {
+    use std::{
+        fmt::Debug,
+        ops::{Deref, DerefMut},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        },
+    };
+
+    // Like `RefCell`, these don't poison: a panic in user code shouldn't make the rest
+    // of the reactive system unusable.
+
+    /// A [std::cell::RefCell] backed by a [RwLock].
+    ///
+    /// A borrow that conflicts with one held by another thread waits for it to end. One that
+    /// conflicts with a borrow held by the same thread would wait forever, so it panics
+    /// instead, like [std::cell::RefCell] does.
+    #[derive(Default)]
+    pub(crate) struct RefCell<T: ?Sized> {
+        /// The thread that holds the mutable borrow, if any (see [current_thread]).
+        writer: AtomicUsize,
+        lock: RwLock<T>,
+    }
+
+    impl<T> RefCell<T> {
+        pub fn new(value: T) -> Self {
+            Self {
+                writer: AtomicUsize::new(0),
+                lock: RwLock::new(value),
+            }
+        }
+
+        pub fn take(&self) -> T
+        where
+            T: Default,
+        {
+            std::mem::take(&mut *self.borrow_mut())
+        }
+
+        pub fn replace(&self, value: T) -> T {
+            std::mem::replace(&mut *self.borrow_mut(), value)
+        }
+    }
+
+    impl<T: ?Sized> RefCell<T> {
+        pub fn borrow(&self) -> Ref<'_, T> {
+            if self.writer.load(Ordering::Acquire) == current_thread() {
+                panic!("already mutably borrowed: BorrowError");
+            }
+            let guard = self.lock.read().unwrap_or_else(PoisonError::into_inner);
+            let cell = self.address();
+            READING.with(|reading| reading.borrow_mut().push(cell));
+            Ref { guard, cell }
+        }
+
+        pub fn borrow_mut(&self) -> RefMut<'_, T> {
+            let thread = current_thread();
+            if self.writer.load(Ordering::Acquire) == thread
+                || READING.with(|reading| reading.borrow().contains(&self.address()))
+            {
+                panic!("already borrowed: BorrowMutError");
+            }
+            let guard = self.lock.write().unwrap_or_else(PoisonError::into_inner);
+            self.writer.store(thread, Ordering::Release);
+            RefMut {
+                guard,
+                writer: &self.writer,
+            }
+        }
+    }
+
+    impl<T: ?Sized> RefCell<T> {
+        fn address(&self) -> usize {
+            self as *const Self as *const () as usize
+        }
+    }
+
+    impl<T: Debug + ?Sized> Debug for RefCell<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_tuple("RefCell").field(&&*self.borrow()).finish()
+        }
+    }
+
+    thread_local! {
+        /// The addresses of the [RefCell]s this thread holds shared borrows of, once for
+        /// each borrow.
+        static READING: std::cell::RefCell<Vec<usize>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    /// A shared borrow of a [RefCell].
+    pub(crate) struct Ref<'a, T: ?Sized> {
+        guard: RwLockReadGuard<'a, T>,
+        cell: usize,
+    }
+
+    impl<T: ?Sized> Deref for Ref<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.guard
+        }
+    }
+
+    impl<T: ?Sized> Drop for Ref<'_, T> {
+        fn drop(&mut self) {
+            // the thread-local may already be gone if this is dropped while the thread exits
+            _ = READING.try_with(|reading| {
+                let mut reading = reading.borrow_mut();
+                if let Some(index) = reading.iter().rposition(|cell| *cell == self.cell) {
+                    reading.swap_remove(index);
+                }
+            });
+        }
+    }
+
+    /// A mutable borrow of a [RefCell].
+    pub(crate) struct RefMut<'a, T: ?Sized> {
+        guard: RwLockWriteGuard<'a, T>,
+        writer: &'a AtomicUsize,
+    }
+
+    impl<T: ?Sized> Deref for RefMut<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.guard
+        }
+    }
+
+    impl<T: ?Sized> DerefMut for RefMut<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            &mut self.guard
+        }
+    }
+
+    impl<T: ?Sized> Drop for RefMut<'_, T> {
+        fn drop(&mut self) {
+            // still holding the lock, so no other thread can have set this
+            self.writer.store(0, Ordering::Release);
+        }
+    }
+
+    /// A nonzero id for the current thread.
+    fn current_thread() -> usize {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+        thread_local! {
+            static ID: usize = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        }
+        ID.with(|id| *id)
+    }
+
+    /// A [std::cell::Cell] backed by a [Mutex].
+    #[derive(Default)]
+    pub(crate) struct Cell<T>(Mutex<T>);
+
+    impl<T> Cell<T> {
+        pub fn new(value: T) -> Self {
+            Self(Mutex::new(value))
+        }
+
+        pub fn get(&self) -> T
+        where
+            T: Copy,
+        {
+            *self.lock()
+        }
+
+        pub fn set(&self, value: T) {
+            *self.lock() = value;
+        }
+
+        pub fn replace(&self, value: T) -> T {
+            std::mem::replace(&mut *self.lock(), value)
+        }
+
+        fn lock(&self) -> std::sync::MutexGuard<'_, T> {
+            self.0.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl<T: Copy + Debug> Debug for Cell<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Cell").field("value", &self.get()).finish()
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{create_signal, with_runtime, ReadSignal, RuntimeId, Scope, WriteSignal};
+
+/// Creates a [Transition], which can be used to make a set of changes without showing
+/// a [Suspense](https://docs.rs/leptos_core/latest/leptos_core/fn.Suspense.html) fallback
+/// while any [Resource](crate::Resource)s they cause to load are pending.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(|cx| {
+/// let (id, set_id) = create_signal(cx, 0);
+/// let user = create_resource(cx, id, |id| async move { format!("user {id}") });
+/// let transition = use_transition(cx);
+///
+/// // any resources that load because of this change are tracked by the transition
+/// transition.start(move || set_id(1));
+///
+/// // `is_pending` is true until they have all resolved
+/// assert!(!transition.is_pending());
+/// assert_eq!(user(), Some("user 1".to_string()));
+/// # }).dispose();
+/// ```
+pub fn use_transition(cx: Scope) -> Transition {
+    let (pending_resources, set_pending_resources) = create_signal(cx, 0);
+    Transition {
+        runtime: cx.runtime,
+        pending_resources,
+        set_pending_resources,
+    }
+}
+
+/// Tracks the [Resource](crate::Resource)s that load as a result of the changes made in
+/// [Transition::start]. See [use_transition].
+#[derive(Copy, Clone, Debug)]
+pub struct Transition {
+    runtime: RuntimeId,
+    pending_resources: ReadSignal<usize>,
+    set_pending_resources: WriteSignal<usize>,
+}
+
+impl PartialEq for Transition {
+    fn eq(&self, other: &Self) -> bool {
+        self.pending_resources.id == other.pending_resources.id
+    }
+}
+
+impl Eq for Transition {}
+
+impl Transition {
+    /// Runs the given function as part of this transition: any resources that start
+    /// loading because of the changes it makes (including in effects that run in
+    /// response to them) will keep the transition pending until they resolve.
+    pub fn start(&self, f: impl FnOnce()) {
+        with_runtime(self.runtime, |runtime| runtime.start_transition(*self, f))
+    }
+
+    /// Whether any resources loaded as part of this transition are still pending.
+    /// This is reactive: reading it subscribes the running effect to changes.
+    pub fn is_pending(&self) -> bool {
+        self.pending_resources.with(|n| *n > 0)
+    }
+
+    pub(crate) fn increment(&self) {
+        self.set_pending_resources.update(|n| *n += 1);
+    }
+
+    pub(crate) fn decrement(&self) {
+        self.set_pending_resources
+            .update(|n| *n = n.saturating_sub(1));
+    }
+}
@@ -0,0 +1,98 @@
+use leptos_reactive::{
+    create_action, create_isomorphic_effect, create_resource, run_scope_undisposed, TestExecutor,
+};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn action_tracks_input_and_pending_while_running() {
+    let executor = TestExecutor::new();
+    let states = Arc::new(Mutex::new(Vec::new()));
+    let (action, disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        let states = Arc::clone(&states);
+        move |cx| {
+            cx.set_executor(executor);
+            let action = create_action(cx, |name: &String| {
+                let name = name.clone();
+                async move { name.len() }
+            });
+            create_isomorphic_effect(cx, {
+                let action = action.clone();
+                move |_| {
+                    states
+                        .lock()
+                        .unwrap()
+                        .push((action.pending()(), action.input()()));
+                }
+            });
+            action
+        }
+    });
+
+    action.dispatch("Alice".to_string());
+    assert_eq!(
+        states.lock().unwrap().last(),
+        Some(&(true, Some("Alice".to_string())))
+    );
+
+    executor.run_until_stalled();
+    assert_eq!(states.lock().unwrap().last(), Some(&(false, None)));
+    assert_eq!(action.value()(), Some(5));
+    assert_eq!(action.version()(), 1);
+
+    disposer.dispose();
+}
+
+#[test]
+fn resource_refetches_after_action() {
+    let executor = TestExecutor::new();
+    let todos = Arc::new(Mutex::new(Vec::new()));
+    let ((add_todo, count), disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        let todos = Arc::clone(&todos);
+        move |cx| {
+            cx.set_executor(executor);
+            let add_todo = create_action(cx, {
+                let todos = Arc::clone(&todos);
+                move |title: &String| {
+                    todos.lock().unwrap().push(title.clone());
+                    async {}
+                }
+            });
+            let count = create_resource(cx, add_todo.version(), move |_| {
+                let count = todos.lock().unwrap().len();
+                async move { count }
+            });
+            (add_todo, count)
+        }
+    });
+    executor.run_until_stalled();
+    assert_eq!(count(), Some(0));
+
+    add_todo.dispatch("Buy milk".to_string());
+    add_todo.dispatch("Walk dog".to_string());
+    executor.run_until_stalled();
+    assert_eq!(count(), Some(2));
+    assert_eq!(add_todo.version()(), 2);
+
+    disposer.dispose();
+}
+
+#[test]
+fn action_finishing_after_scope_is_disposed_is_ignored() {
+    let executor = TestExecutor::new();
+    let (action, disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        move |cx| {
+            cx.set_executor(executor);
+            create_action(cx, |n: &i32| {
+                let n = *n;
+                async move { n * 2 }
+            })
+        }
+    });
+
+    action.dispatch(21);
+    disposer.dispose();
+    executor.run_until_stalled();
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "debug")]
+
+use leptos_reactive::{create_isomorphic_effect, create_memo, create_scope, create_signal};
+
+#[test]
+fn allocations_record_where_they_were_created() {
+    create_scope(|cx| {
+        let line = line!() + 1;
+        let (count, _) = create_signal(cx, 0);
+        let double = create_memo(cx, move |_| count() * 2);
+        create_isomorphic_effect(cx, move |_| double());
+
+        let report = cx.allocations();
+        let counts = report.counts();
+        assert_eq!((counts.scopes, counts.signals, counts.effects), (1, 2, 2));
+
+        let root = &report.scopes[0];
+        assert_eq!(root.parent, None);
+        let created_at = root.signals[0].created_at.unwrap();
+        assert_eq!(created_at.file(), file!());
+        assert_eq!(created_at.line(), line);
+        // the memo's signal and computation both point to where it was created
+        assert_eq!(root.signals[1].created_at.unwrap().line(), line + 1);
+        assert_eq!(root.effects[0].created_at.unwrap().line(), line + 1);
+        assert_eq!(root.effects[1].created_at.unwrap().line(), line + 2);
+    })
+    .dispose();
+}
+
+#[test]
+fn forgotten_child_scope_shows_up_in_report() {
+    create_scope(|cx| {
+        let before = cx.allocations().counts();
+
+        let line = line!() + 1;
+        let disposer = cx.child_scope(|cx| {
+            create_signal(cx, 0);
+        });
+        // forgetting to dispose of the scope leaks its signal
+        std::mem::forget(disposer);
+
+        let report = cx.allocations();
+        assert_eq!(report.counts().signals, before.signals + 1);
+        let leaked = report.scopes.iter().find(|s| s.parent.is_some()).unwrap();
+        assert_eq!(leaked.created_at.unwrap().line(), line);
+        assert!(report
+            .to_string()
+            .contains(&format!("created at {}:{line}", file!())));
+    })
+    .dispose();
+}
+
+#[test]
+#[should_panic(expected = "expected AllocationCounts")]
+fn assert_allocations_panics_on_mismatch() {
+    create_scope(|cx| {
+        let before = cx.allocations().counts();
+        create_signal(cx, 0);
+        cx.assert_allocations(before);
+    })
+    .dispose();
+}
+
+#[test]
+fn effect_reruns_do_not_pile_up_allocations() {
+    create_scope(|cx| {
+        let (count, set_count) = create_signal(cx, 0);
+        create_isomorphic_effect(cx, move |_| {
+            let (local, _) = create_signal(cx, count());
+            create_memo(cx, move |_| local() * 2);
+        });
+        let before = cx.allocations().counts();
+
+        for n in 1..10 {
+            set_count(n);
+        }
+        cx.assert_allocations(before);
+    })
+    .dispose();
+}
@@ -0,0 +1,116 @@
+use leptos_reactive::{batch, create_effect, create_memo, create_scope, create_signal};
+
+#[test]
+fn batch_defers_effects_until_end() {
+    use std::sync::{Arc, Mutex};
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let (b, set_b) = create_signal(cx, 0);
+
+        // record every value the effect observes
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        create_effect(cx, {
+            let seen = seen.clone();
+            move |_| {
+                seen.lock().unwrap().push((a(), b()));
+            }
+        });
+
+        assert_eq!(*seen.lock().unwrap(), vec![(0, 0)]);
+
+        batch(cx, move || {
+            set_a(1);
+            set_b(2);
+            set_a(3);
+        });
+
+        // the effect only ran once, and never saw an intermediate state
+        assert_eq!(*seen.lock().unwrap(), vec![(0, 0), (3, 2)]);
+    })
+    .dispose()
+}
+
+#[test]
+fn nested_batches_run_effects_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        create_effect(cx, {
+            let runs = runs.clone();
+            move |_| {
+                _ = a();
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        cx.batch(|| {
+            set_a(1);
+            cx.batch(move || set_a(2));
+            // leaving the inner batch doesn't run the effect
+            assert_eq!(runs.load(Ordering::Relaxed), 1);
+            set_a(3);
+        });
+
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        assert_eq!(a(), 3);
+    })
+    .dispose()
+}
+
+#[test]
+fn batch_returns_value() {
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 1);
+        let b = create_memo(cx, move |_| a() * 2);
+
+        let prev = batch(cx, move || {
+            let prev = a();
+            set_a(5);
+            prev
+        });
+
+        assert_eq!(prev, 1);
+        assert_eq!(b(), 10);
+    })
+    .dispose()
+}
+
+#[test]
+fn panicking_batch_stops_batching() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        create_effect(cx, {
+            let runs = runs.clone();
+            move |_| {
+                _ = a();
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            batch(cx, move || {
+                set_a(1);
+                panic!("oops");
+            })
+        }));
+        assert!(res.is_err());
+
+        // effects aren't deferred after the panic
+        set_a(2);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        assert_eq!(a(), 2);
+    })
+    .dispose()
+}
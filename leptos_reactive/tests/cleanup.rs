@@ -0,0 +1,144 @@
+use leptos_reactive::{create_effect, create_scope, create_signal, on_cleanup};
+
+#[test]
+fn cleanup_runs_on_dispose() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let cleaned_up = Arc::new(AtomicBool::new(false));
+
+    let disposer = create_scope({
+        let cleaned_up = cleaned_up.clone();
+        move |cx| {
+            on_cleanup(cx, move || cleaned_up.store(true, Ordering::Relaxed));
+        }
+    });
+
+    assert!(!cleaned_up.load(Ordering::Relaxed));
+    disposer.dispose();
+    assert!(cleaned_up.load(Ordering::Relaxed));
+}
+
+#[test]
+fn cleanup_runs_in_child_scope() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    create_scope(|cx| {
+        let cleaned_up = Arc::new(AtomicUsize::new(0));
+
+        let child = cx.child_scope({
+            let cleaned_up = cleaned_up.clone();
+            move |cx| {
+                on_cleanup(cx, move || _ = cleaned_up.fetch_add(1, Ordering::Relaxed));
+            }
+        });
+
+        assert_eq!(cleaned_up.load(Ordering::Relaxed), 0);
+        child.dispose();
+        assert_eq!(cleaned_up.load(Ordering::Relaxed), 1);
+    })
+    .dispose()
+}
+
+#[test]
+fn effect_cleanup_runs_before_rerun() {
+    use std::sync::{Arc, Mutex};
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        create_effect(cx, {
+            let log = log.clone();
+            move |_| {
+                let value = a();
+                log.lock().unwrap().push(format!("run {value}"));
+                on_cleanup(cx, {
+                    let log = log.clone();
+                    move || log.lock().unwrap().push(format!("cleanup {value}"))
+                });
+            }
+        });
+
+        set_a(1);
+        set_a(2);
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["run 0", "cleanup 0", "run 1", "cleanup 1", "run 2"]
+        );
+    })
+    .dispose()
+}
+
+#[test]
+fn untracked_cleanup_belongs_to_scope() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let cleanups = Arc::new(AtomicUsize::new(0));
+
+    create_scope({
+        let cleanups = cleanups.clone();
+        move |cx| {
+            let (a, set_a) = create_signal(cx, 0);
+
+            create_effect(cx, move |_| {
+                _ = a();
+                let cleanups = cleanups.clone();
+                cx.untrack(|| on_cleanup(cx, move || _ = cleanups.fetch_add(1, Ordering::Relaxed)));
+            });
+
+            set_a(1);
+        }
+    })
+    .dispose();
+
+    // both cleanups only ran when the scope was disposed
+    assert_eq!(cleanups.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn cleanup_in_scope_created_by_effect_runs_when_that_scope_is_disposed() {
+    use std::sync::{Arc, Mutex};
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        // a ScopeDisposer is only Send and Sync, as the effect needs it to be, with the
+        // `multithreaded` feature
+        #[allow(clippy::arc_with_non_send_sync)]
+        let child = Arc::new(Mutex::new(None));
+
+        create_effect(cx, {
+            let log = log.clone();
+            let child = child.clone();
+            move |_| {
+                if a() == 0 {
+                    let log = log.clone();
+                    *child.lock().unwrap() = Some(cx.child_scope(move |cx| {
+                        on_cleanup(cx, move || log.lock().unwrap().push("child cleanup"));
+                    }));
+                }
+            }
+        });
+
+        log.lock().unwrap().push("before dispose");
+        child.lock().unwrap().take().unwrap().dispose();
+        log.lock().unwrap().push("after dispose");
+        set_a(1);
+        log.lock().unwrap().push("after set");
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "before dispose",
+                "child cleanup",
+                "after dispose",
+                "after set"
+            ]
+        );
+    })
+    .dispose()
+}
@@ -0,0 +1,155 @@
+use leptos_reactive::{
+    create_effect, create_scope, create_signal_map, create_signal_vec, on_cleanup, MapDiff, VecDiff,
+};
+use std::{
+    collections::HashMap,
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+#[test]
+fn signal_vec_sends_diffs_to_subscribers() {
+    create_scope(|cx| {
+        let list = create_signal_vec(cx, vec![1, 2]);
+        let diffs = list.subscribe(cx);
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        create_effect(cx, {
+            let received = Arc::clone(&received);
+            move |_| received.lock().unwrap().extend(diffs.take())
+        });
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![VecDiff::Replace(vec![1, 2])]
+        );
+
+        list.push(3);
+        list.set(0, 10);
+        list.move_item(2, 0);
+        list.remove(1);
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                VecDiff::Replace(vec![1, 2]),
+                VecDiff::Push(3),
+                VecDiff::Set {
+                    index: 0,
+                    value: 10
+                },
+                VecDiff::Move { from: 2, to: 0 },
+                VecDiff::RemoveAt { index: 1 },
+            ]
+        );
+
+        // applying the diffs in order rebuilds the same list
+        let mut copy = Vec::new();
+        for diff in received.lock().unwrap().iter().cloned() {
+            diff.apply_to(&mut copy);
+        }
+        assert_eq!(copy, list.get());
+        assert_eq!(copy, vec![3, 2]);
+    })
+    .dispose();
+}
+
+#[test]
+fn signal_map_sends_diffs_to_subscribers() {
+    create_scope(|cx| {
+        let map = create_signal_map(cx, HashMap::new());
+        let diffs = map.subscribe(cx);
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        create_effect(cx, {
+            let received = Arc::clone(&received);
+            move |_| received.lock().unwrap().extend(diffs.take())
+        });
+
+        map.insert("a", 1);
+        map.insert("b", 2);
+        map.remove(&"a");
+        // removing a missing key doesn't send a diff
+        map.remove(&"c");
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                MapDiff::Replace(HashMap::new()),
+                MapDiff::Insert { key: "a", value: 1 },
+                MapDiff::Insert { key: "b", value: 2 },
+                MapDiff::Remove { key: "a" },
+            ]
+        );
+        assert_eq!(map.get(&"b"), Some(2));
+        assert_eq!(map.len(), 1);
+    })
+    .dispose();
+}
+
+#[test]
+fn invalid_change_is_not_sent_to_subscribers() {
+    create_scope(|cx| {
+        let list = create_signal_vec(cx, vec![1, 2]);
+        let diffs = list.subscribe(cx);
+        assert_eq!(diffs.take(), vec![VecDiff::Replace(vec![1, 2])]);
+
+        let out_of_bounds = std::panic::catch_unwind(AssertUnwindSafe(|| list.set(5, 10)));
+        assert!(out_of_bounds.is_err());
+
+        // a subscriber applying its diffs would panic too, so it never receives it
+        assert_eq!(diffs.take(), vec![]);
+        assert_eq!(list.get(), vec![1, 2]);
+    })
+    .dispose();
+}
+
+#[test]
+fn mapped_signal_vec_only_maps_changed_values() {
+    create_scope(|cx| {
+        let rows = create_signal_vec(cx, vec![1, 2, 3]);
+        let mapped_count = Arc::new(AtomicUsize::new(0));
+        let cleaned_up = Arc::new(Mutex::new(Vec::new()));
+        let mapped = rows.map(cx, {
+            let mapped_count = Arc::clone(&mapped_count);
+            let cleaned_up = Arc::clone(&cleaned_up);
+            move |cx, n| {
+                mapped_count.fetch_add(1, Ordering::Relaxed);
+                let (n, cleaned_up) = (*n, Arc::clone(&cleaned_up));
+                on_cleanup(cx, move || cleaned_up.lock().unwrap().push(n));
+                n * 10
+            }
+        });
+        let diffs = mapped.subscribe(cx);
+        assert_eq!(diffs.take(), vec![VecDiff::Replace(vec![10, 20, 30])]);
+        assert_eq!(mapped_count.load(Ordering::Relaxed), 3);
+
+        rows.set(1, 4);
+        rows.move_item(0, 2);
+        rows.push(5);
+        rows.remove(0);
+
+        // each change maps at most one value, and is passed on as a change to the mapped list
+        assert_eq!(mapped_count.load(Ordering::Relaxed), 5);
+        assert_eq!(
+            diffs.take(),
+            vec![
+                VecDiff::Set {
+                    index: 1,
+                    value: 40
+                },
+                VecDiff::Move { from: 0, to: 2 },
+                VecDiff::Push(50),
+                VecDiff::RemoveAt { index: 0 },
+            ]
+        );
+        assert_eq!(mapped.get(), vec![30, 10, 50]);
+
+        // the scopes of replaced and removed values are disposed
+        assert_eq!(*cleaned_up.lock().unwrap(), vec![2, 4]);
+        rows.clear();
+        assert_eq!(*cleaned_up.lock().unwrap(), vec![2, 4, 3, 1, 5]);
+        assert_eq!(mapped.get(), Vec::<i32>::new());
+    })
+    .dispose();
+}
@@ -0,0 +1,60 @@
+use leptos_reactive::{
+    create_context_key, create_scope, expect_context, provide_context, provide_context_with_key,
+    use_context, use_context_with_key,
+};
+
+#[test]
+fn keyed_contexts_of_the_same_type_are_distinct() {
+    create_scope(|cx| {
+        let title = create_context_key::<String>();
+        let subtitle = create_context_key::<String>();
+        provide_context(cx, "by type".to_string());
+        provide_context_with_key(cx, title, "Title".to_string());
+
+        cx.child_scope(|cx| {
+            // a child can shadow one key without affecting the others
+            provide_context_with_key(cx, subtitle, "Subtitle".to_string());
+            provide_context_with_key(cx, title, "Inner title".to_string());
+
+            cx.child_scope(|cx| {
+                assert_eq!(
+                    use_context_with_key(cx, title).as_deref(),
+                    Some("Inner title")
+                );
+                assert_eq!(
+                    use_context_with_key(cx, subtitle).as_deref(),
+                    Some("Subtitle")
+                );
+                assert_eq!(use_context::<String>(cx).as_deref(), Some("by type"));
+            });
+        });
+
+        assert_eq!(use_context_with_key(cx, title).as_deref(), Some("Title"));
+        assert_eq!(use_context_with_key(cx, subtitle), None);
+    })
+    .dispose();
+}
+
+#[test]
+#[should_panic(expected = "expected a context of type alloc::string::String")]
+fn expect_context_panics_with_type_name() {
+    create_scope(|cx| {
+        provide_context(cx, 42_i32);
+        assert_eq!(expect_context::<i32>(cx), 42);
+        expect_context::<String>(cx);
+    })
+    .dispose();
+}
+
+#[test]
+fn expect_context_reports_call_site() {
+    let err = std::panic::catch_unwind(|| {
+        create_scope(|cx| {
+            expect_context::<bool>(cx);
+        })
+        .dispose();
+    })
+    .unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("tests/context.rs"), "{message}");
+}
@@ -1,89 +1,286 @@
-use leptos_reactive::{create_effect, create_memo, create_scope, create_signal};
+use leptos_reactive::{
+    create_effect, create_memo, create_rw_signal, create_scope, create_signal, untrack,
+};
 
 #[test]
 fn effect_runs() {
-    use std::cell::RefCell;
-    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
 
     create_scope(|cx| {
         let (a, set_a) = create_signal(cx, -1);
 
         // simulate an arbitrary side effect
-        let b = Rc::new(RefCell::new(String::new()));
+        let b = Arc::new(Mutex::new(String::new()));
 
         create_effect(cx, {
             let b = b.clone();
             move |_| {
                 let formatted = format!("Value is {}", a());
-                *b.borrow_mut() = formatted;
+                *b.lock().unwrap() = formatted;
             }
         });
 
-        assert_eq!(b.borrow().as_str(), "Value is -1");
+        assert_eq!(b.lock().unwrap().as_str(), "Value is -1");
 
         set_a(1);
 
-        assert_eq!(b.borrow().as_str(), "Value is 1");
+        assert_eq!(b.lock().unwrap().as_str(), "Value is 1");
     })
     .dispose()
 }
 
 #[test]
 fn effect_tracks_memo() {
-    use std::cell::RefCell;
-    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
 
     create_scope(|cx| {
         let (a, set_a) = create_signal(cx, -1);
         let b = create_memo(cx, move |_| format!("Value is {}", a()));
 
         // simulate an arbitrary side effect
-        let c = Rc::new(RefCell::new(String::new()));
+        let c = Arc::new(Mutex::new(String::new()));
 
         create_effect(cx, {
             let c = c.clone();
             move |_| {
-                *c.borrow_mut() = b();
+                *c.lock().unwrap() = b();
             }
         });
 
         assert_eq!(b().as_str(), "Value is -1");
-        assert_eq!(c.borrow().as_str(), "Value is -1");
+        assert_eq!(c.lock().unwrap().as_str(), "Value is -1");
 
         set_a(1);
 
         assert_eq!(b().as_str(), "Value is 1");
-        assert_eq!(c.borrow().as_str(), "Value is 1");
+        assert_eq!(c.lock().unwrap().as_str(), "Value is 1");
     })
     .dispose()
 }
 
 #[test]
 fn untrack_mutes_effect() {
-    use std::cell::RefCell;
-    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
 
     create_scope(|cx| {
         let (a, set_a) = create_signal(cx, -1);
 
         // simulate an arbitrary side effect
-        let b = Rc::new(RefCell::new(String::new()));
+        let b = Arc::new(Mutex::new(String::new()));
 
         create_effect(cx, {
             let b = b.clone();
             move |_| {
                 let formatted = format!("Value is {}", cx.untrack(a));
-                *b.borrow_mut() = formatted;
+                *b.lock().unwrap() = formatted;
             }
         });
 
         assert_eq!(a(), -1);
-        assert_eq!(b.borrow().as_str(), "Value is -1");
+        assert_eq!(b.lock().unwrap().as_str(), "Value is -1");
 
         set_a(1);
 
         assert_eq!(a(), 1);
-        assert_eq!(b.borrow().as_str(), "Value is -1");
+        assert_eq!(b.lock().unwrap().as_str(), "Value is -1");
+    })
+    .dispose()
+}
+
+#[test]
+fn untracked_accessors_mute_effect() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 1);
+        let b = create_rw_signal(cx, 2);
+        let c = create_memo(cx, move |_| a() * 10);
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        create_effect(cx, {
+            let runs = Arc::clone(&runs);
+            move |_| {
+                let sum = a.get_untracked() + b.with_untracked(|b| *b) + c.get_untracked();
+                runs.fetch_add(1, Ordering::Relaxed);
+                sum
+            }
+        });
+
+        set_a(2);
+        b.set(3);
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        // the memo still updates, even though it was read untracked
+        assert_eq!(c(), 20);
+    })
+    .dispose()
+}
+
+#[test]
+fn effects_run_inside_untrack_still_track() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let (b, set_b) = create_signal(cx, 0);
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        untrack(|| {
+            // created, and so first run, inside `untrack`
+            create_effect(cx, {
+                let runs = Arc::clone(&runs);
+                move |_| {
+                    a();
+                    runs.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        });
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+
+        // an effect that sets a signal inside `untrack`
+        create_effect(cx, move |_| {
+            let b = b();
+            untrack(|| set_a.set_if_changed(b));
+        });
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+
+        // the first effect re-runs when `a` is set inside the second effect's `untrack`
+        set_b(1);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        set_a(2);
+        assert_eq!(runs.load(Ordering::Relaxed), 3);
+    })
+    .dispose()
+}
+
+#[test]
+fn effect_sees_consistent_diamond() {
+    use std::sync::{Arc, Mutex};
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 1);
+        let b = create_memo(cx, move |_| a() * 2);
+        let c = create_memo(cx, move |_| a() * 3);
+
+        // record every pair of values the effect observes
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        create_effect(cx, {
+            let seen = seen.clone();
+            move |_| {
+                seen.lock().unwrap().push((b(), c()));
+            }
+        });
+
+        assert_eq!(*seen.lock().unwrap(), vec![(2, 3)]);
+
+        set_a(2);
+
+        // runs exactly once per change, and never sees a half-updated state like (4, 3)
+        assert_eq!(*seen.lock().unwrap(), vec![(2, 3), (4, 6)]);
+    })
+    .dispose()
+}
+
+#[test]
+fn effect_disposes_what_it_created_before_rerunning() {
+    use std::sync::{Arc, Mutex};
+
+    create_scope(|cx| {
+        let (show, set_show) = create_signal(cx, true);
+        let (count, set_count) = create_signal(cx, 0);
+
+        // the nested state created by each run, as conditional rendering would
+        let created = Arc::new(Mutex::new(Vec::new()));
+        let inner_runs = Arc::new(Mutex::new(Vec::new()));
+        create_effect(cx, {
+            let created = created.clone();
+            let inner_runs = inner_runs.clone();
+            move |_| {
+                if show() {
+                    let (local, _) = create_signal(cx, "local");
+                    created.lock().unwrap().push(local);
+
+                    let run = created.lock().unwrap().len();
+                    let inner_runs = inner_runs.clone();
+                    create_effect(cx, move |_| inner_runs.lock().unwrap().push((run, count())));
+                }
+            }
+        });
+
+        set_count(1);
+        assert_eq!(*inner_runs.lock().unwrap(), vec![(1, 0), (1, 1)]);
+
+        // re-running disposes of the first run's signal and nested effect
+        set_show(false);
+        set_show(true);
+        set_count(2);
+        assert_eq!(
+            *inner_runs.lock().unwrap(),
+            vec![(1, 0), (1, 1), (2, 1), (2, 2)]
+        );
+        assert!(created.lock().unwrap()[0].try_get().is_err());
+        assert_eq!(created.lock().unwrap()[1].try_get(), Ok("local"));
+    })
+    .dispose()
+}
+
+#[test]
+fn effect_owned_state_can_outlive_the_run_with_untrack() {
+    create_scope(|cx| {
+        let (count, set_count) = create_signal(cx, 0);
+
+        let kept = create_rw_signal(cx, None);
+        let memo = create_memo(cx, move |_| {
+            count();
+            // created inside `untrack`, so it isn't owned by the run
+            if kept.get_untracked().is_none() {
+                kept.set(Some(untrack(|| create_signal(cx, "kept").0)));
+            }
+            let (owned, _) = create_signal(cx, "owned");
+            owned
+        });
+        let first = memo.get();
+
+        set_count(1);
+        let second = memo.get();
+        assert!(first.try_get().is_err());
+        assert_eq!(second.try_get(), Ok("owned"));
+        assert_eq!(kept.get_untracked().unwrap().try_get(), Ok("kept"));
+    })
+    .dispose()
+}
+
+#[test]
+fn panicking_effect_does_not_stop_later_updates() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let (b, set_b) = create_signal(cx, 0);
+
+        create_effect(cx, move |_| {
+            if a() == 1 {
+                panic!("oops");
+            }
+        });
+        let runs = Arc::new(AtomicUsize::new(0));
+        create_effect(cx, {
+            let runs = runs.clone();
+            move |_| {
+                _ = b();
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        assert!(catch_unwind(AssertUnwindSafe(|| set_a(1))).is_err());
+
+        // effects still run after the panic
+        set_b(1);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
     })
     .dispose()
 }
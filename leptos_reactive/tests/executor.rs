@@ -0,0 +1,116 @@
+#![cfg(not(feature = "ssr"))]
+
+use futures::channel::oneshot;
+use leptos_reactive::{
+    create_action, create_resource_with_initial_value, create_signal, provide_context,
+    run_scope_undisposed, SuspenseContext, TestExecutor,
+};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn resource_loads_interleave_deterministically() {
+    let executor = TestExecutor::new();
+    let senders = Arc::new(Mutex::new(Vec::new()));
+    let ((suspense, set_id, resource), disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        let senders = Arc::clone(&senders);
+        move |cx| {
+            cx.set_executor(executor);
+            let suspense = SuspenseContext::new(cx);
+            provide_context(cx, suspense);
+
+            let (id, set_id) = create_signal(cx, 0);
+            let resource = create_resource_with_initial_value(
+                cx,
+                id,
+                move |id| {
+                    let (tx, rx) = oneshot::channel::<()>();
+                    senders.lock().unwrap().push(tx);
+                    async move {
+                        _ = rx.await;
+                        id
+                    }
+                },
+                None,
+            );
+            (suspense, set_id, resource)
+        }
+    });
+
+    // reading the resource registers it with the suspense context
+    assert_eq!(resource.read(), None);
+    assert_eq!(senders.lock().unwrap().len(), 1);
+
+    // the counter only changes once microtasks are flushed
+    set_id(1);
+    assert_eq!(senders.lock().unwrap().len(), 2);
+    executor.flush_microtasks();
+    assert!(!suspense.ready());
+
+    // the first load finishes after the second one has started, so it's discarded
+    let first = senders.lock().unwrap().remove(0);
+    _ = first.send(());
+    executor.run_until_stalled();
+    assert_eq!(resource.read(), None);
+    assert!(!suspense.ready());
+
+    let second = senders.lock().unwrap().remove(0);
+    _ = second.send(());
+    executor.run_until_stalled();
+    assert_eq!(resource.read(), Some(1));
+    assert!(suspense.ready());
+
+    disposer.dispose();
+}
+
+#[test]
+fn action_runs_only_when_executor_is_driven() {
+    let executor = TestExecutor::new();
+    let (action, disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        move |cx| {
+            cx.set_executor(executor);
+            create_action(cx, |n: &i32| {
+                let n = *n;
+                async move { n * 2 }
+            })
+        }
+    });
+
+    action.dispatch(21);
+    assert!(action.pending().get());
+    assert_eq!(action.value().get(), None);
+
+    executor.run_until_stalled();
+    assert!(!action.pending().get());
+    assert_eq!(action.value().get(), Some(42));
+
+    disposer.dispose();
+}
+
+#[test]
+fn microtasks_can_be_flushed_by_hand() {
+    let executor = TestExecutor::new();
+    let (suspense, disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        move |cx| {
+            cx.set_executor(executor);
+            SuspenseContext::new(cx)
+        }
+    });
+
+    suspense.increment();
+    suspense.increment();
+    assert_eq!(executor.pending_microtasks(), 2);
+    assert!(suspense.ready());
+
+    executor.flush_microtasks();
+    assert_eq!(executor.pending_microtasks(), 0);
+    assert_eq!(suspense.pending_resources.get(), 2);
+
+    suspense.decrement();
+    executor.run_until_stalled();
+    assert_eq!(suspense.pending_resources.get(), 1);
+
+    disposer.dispose();
+}
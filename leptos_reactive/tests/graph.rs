@@ -0,0 +1,111 @@
+#![cfg(feature = "debug")]
+
+use leptos_reactive::{
+    create_isomorphic_effect, create_memo, create_scope, create_signal, name_effect, DebugName,
+};
+
+#[test]
+fn graph_lists_nodes_with_their_edges_and_owners() {
+    create_scope(|cx| {
+        let cx = cx.debug_name("root");
+        let (count, set_count) = create_signal(cx, 0);
+        let count = count.debug_name("count");
+        let double = create_memo(cx, move |_| count() * 2).debug_name("double");
+
+        let child = cx.child_scope(move |cx| {
+            let cx = cx.debug_name("child");
+            create_isomorphic_effect(cx, move |_| {
+                name_effect(cx, "log");
+                double()
+            });
+        });
+
+        set_count(1);
+
+        let graph = cx.reactive_graph();
+        let root = graph
+            .scopes
+            .iter()
+            .find(|s| s.name.as_deref() == Some("root"))
+            .unwrap();
+        let child_scope = graph
+            .scopes
+            .iter()
+            .find(|s| s.name.as_deref() == Some("child"))
+            .unwrap();
+        assert_eq!(child_scope.parent, Some(root.id));
+
+        let count = graph
+            .signals
+            .iter()
+            .find(|s| s.name.as_deref() == Some("count"))
+            .unwrap();
+        assert_eq!(count.scope, Some(root.id));
+        assert_eq!(count.memo, None);
+
+        let double = graph
+            .signals
+            .iter()
+            .find(|s| s.name.as_deref() == Some("double"))
+            .unwrap();
+        let computation = graph.effect(double.memo.unwrap()).unwrap();
+        assert_eq!(computation.name.as_deref(), Some("double"));
+        assert_eq!(computation.sources, vec![count.id]);
+        assert_eq!(computation.triggered_by, Some(count.id));
+
+        let log = graph
+            .effects
+            .iter()
+            .find(|e| e.name.as_deref() == Some("log"))
+            .unwrap();
+        assert_eq!(log.scope, Some(child_scope.id));
+        assert_eq!(log.sources, vec![double.id]);
+        assert_eq!(double.subscribers, vec![log.id]);
+        assert_eq!(log.runs, 2);
+        assert_eq!(log.triggered_by, Some(double.id));
+
+        // disposing of a scope removes everything it owned
+        child.dispose();
+        let graph = cx.reactive_graph();
+        assert!(graph
+            .scopes
+            .iter()
+            .all(|s| s.name.as_deref() != Some("child")));
+        assert!(graph
+            .effects
+            .iter()
+            .all(|e| e.name.as_deref() != Some("log")));
+    })
+    .dispose();
+}
+
+#[test]
+fn graph_exports_json_and_dot() {
+    create_scope(|cx| {
+        let (count, _) = create_signal(cx, 0);
+        let count = count.debug_name("count");
+        create_isomorphic_effect(cx, move |_| {
+            name_effect(cx, "effect with \"quotes\"");
+            count()
+        });
+
+        let graph = cx.reactive_graph();
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(json["signals"][0]["name"], "count");
+        assert_eq!(json["effects"][0]["runs"], 1);
+
+        let signal = graph.signals[0].id;
+        let effect = graph.effects[0].id;
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph reactive_graph {"));
+        assert!(dot.contains("subgraph \"cluster_scope_"));
+        assert!(dot.contains("label = \"count (signal "));
+        assert!(dot.contains("label = \"effect with \\\"quotes\\\" (effect "));
+        assert!(dot.contains(&format!(
+            "\"signal_{:?}\" -> \"effect_{:?}\";",
+            slotmap::Key::data(&signal),
+            slotmap::Key::data(&effect)
+        )));
+    })
+    .dispose();
+}
@@ -0,0 +1,108 @@
+use leptos_reactive::{create_effect, create_history_signal, create_scope, HistorySnapshot};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[test]
+fn history_signal_undoes_and_redoes_within_capacity() {
+    create_scope(|cx| {
+        let count = create_history_signal(cx, 0, 2);
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        create_effect(cx, {
+            let runs = Arc::clone(&runs);
+            move |_| {
+                count();
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        let can_undo = Arc::new(AtomicBool::new(false));
+        create_effect(cx, {
+            let can_undo = Arc::clone(&can_undo);
+            move |_| can_undo.store(count.can_undo(), Ordering::Relaxed)
+        });
+        assert!(!can_undo.load(Ordering::Relaxed));
+
+        count.set(1);
+        count.set(2);
+        count.update(|n| *n += 1);
+        assert!(can_undo.load(Ordering::Relaxed));
+        // each update notifies readers once
+        assert_eq!(runs.load(Ordering::Relaxed), 4);
+
+        // only the last two values are kept
+        count.undo();
+        count.undo();
+        assert_eq!(count(), 1);
+        assert!(!can_undo.load(Ordering::Relaxed));
+        count.undo();
+        assert_eq!(count(), 1);
+        assert_eq!(runs.load(Ordering::Relaxed), 6);
+
+        count.redo();
+        assert_eq!(count(), 2);
+        assert!(count.can_redo());
+        count.redo();
+        assert_eq!(count(), 3);
+        assert!(!count.can_redo());
+    })
+    .dispose();
+}
+
+#[test]
+fn history_signal_coalesces_rapid_updates() {
+    create_scope(|cx| {
+        let text = create_history_signal(cx, String::new(), 10).coalesce(Duration::from_secs(60));
+        for c in "word".chars() {
+            text.update(|text| text.push(c));
+        }
+        assert_eq!(text(), "word");
+
+        text.undo();
+        assert_eq!(text(), "");
+        assert!(!text.can_undo());
+
+        // updates after an undo start a new step
+        text.redo();
+        text.update(|text| text.push('s'));
+        text.undo();
+        assert_eq!(text(), "word");
+    })
+    .dispose();
+}
+
+#[test]
+fn history_signal_snapshots_and_restores() {
+    create_scope(|cx| {
+        let count = create_history_signal(cx, 0, 10);
+        count.set(1);
+        count.set(2);
+        count.undo();
+
+        let snapshot = count.snapshot();
+        assert_eq!(
+            snapshot,
+            HistorySnapshot {
+                past: vec![0],
+                present: 1,
+                future: vec![2]
+            }
+        );
+
+        count.set(5);
+        count.set(6);
+        count.restore(snapshot);
+        assert_eq!(count(), 1);
+        count.redo();
+        assert_eq!(count(), 2);
+        count.undo();
+        count.undo();
+        assert_eq!(count(), 0);
+    })
+    .dispose();
+}
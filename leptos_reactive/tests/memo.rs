@@ -47,10 +47,13 @@ fn nested_memos() {
 
 #[test]
 fn memo_runs_only_when_inputs_change() {
-    use std::{cell::Cell, rc::Rc};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
 
     create_scope(|cx| {
-        let call_count = Rc::new(Cell::new(0));
+        let call_count = Arc::new(AtomicUsize::new(0));
         let (a, set_a) = create_signal(cx, 0);
         let (b, _) = create_signal(cx, 0);
         let (c, _) = create_signal(cx, 0);
@@ -61,12 +64,13 @@ fn memo_runs_only_when_inputs_change() {
         let c = create_memo(cx, {
             let call_count = call_count.clone();
             move |_| {
-                call_count.set(call_count.get() + 1);
+                call_count.fetch_add(1, Ordering::Relaxed);
                 a() + b() + c()
             }
         });
 
-        assert_eq!(call_count.get(), 1);
+        // memos are lazy, so we haven't run the calculation yet
+        assert_eq!(call_count.load(Ordering::Relaxed), 0);
 
         // here we access the value a bunch of times
         assert_eq!(c(), 0);
@@ -76,12 +80,205 @@ fn memo_runs_only_when_inputs_change() {
         assert_eq!(c(), 0);
 
         // we've still only called the memo calculation once
-        assert_eq!(call_count.get(), 1);
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
 
         // and we only call it again when an input changes
         set_a(1);
         assert_eq!(c(), 1);
-        assert_eq!(call_count.get(), 2);
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+    })
+    .dispose()
+}
+
+#[test]
+fn diamond_problem() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    create_scope(|cx| {
+        let (name, set_name) = create_signal(cx, "Greg Johnston".to_string());
+        let first = create_memo(cx, move |_| {
+            name().split_whitespace().next().unwrap().to_string()
+        });
+        let last = create_memo(cx, move |_| {
+            name().split_whitespace().nth(1).unwrap().to_string()
+        });
+
+        let combined_count = Arc::new(AtomicUsize::new(0));
+        let combined = create_memo(cx, {
+            let combined_count = Arc::clone(&combined_count);
+            move |_| {
+                combined_count.fetch_add(1, Ordering::Relaxed);
+                format!("{} {}", first(), last())
+            }
+        });
+
+        assert_eq!(first(), "Greg");
+        assert_eq!(last(), "Johnston");
+        assert_eq!(combined(), "Greg Johnston");
+
+        set_name("Will Smith".to_string());
+        assert_eq!(first(), "Will");
+        assert_eq!(last(), "Smith");
+        assert_eq!(combined(), "Will Smith");
+        // should not have run the memo logic twice, even
+        // though both paths have been updated
+        assert_eq!(combined_count.load(Ordering::Relaxed), 2);
+    })
+    .dispose()
+}
+
+#[test]
+fn memo_doesnt_notify_if_value_unchanged() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    create_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 1);
+        let is_even = create_memo(cx, move |_| a() % 2 == 0);
+
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let label = create_memo(cx, {
+            let call_count = Arc::clone(&call_count);
+            move |_| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                if is_even() {
+                    "even"
+                } else {
+                    "odd"
+                }
+            }
+        });
+
+        assert_eq!(label(), "odd");
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
+
+        // `is_even` is unchanged, so `label` doesn't re-run
+        set_a(3);
+        assert_eq!(label(), "odd");
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
+
+        set_a(4);
+        assert_eq!(label(), "even");
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+    })
+    .dispose()
+}
+
+#[test]
+fn memo_is_lazy() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    create_scope(|cx| {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let (a, set_a) = create_signal(cx, 0);
+
+        let b = create_memo(cx, {
+            let call_count = call_count.clone();
+            move |_| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                a() * 2
+            }
+        });
+
+        // doesn't run until it's read
+        assert_eq!(call_count.load(Ordering::Relaxed), 0);
+        assert_eq!(b(), 0);
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
+
+        // doesn't re-run when its source changes, if nothing reads it
+        set_a(1);
+        set_a(2);
+        set_a(3);
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
+
+        // runs once when read again, no matter how many times the source changed
+        assert_eq!(b(), 6);
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b(), 6);
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+    })
+    .dispose()
+}
+
+#[test]
+fn observed_memo_runs_once_per_change() {
+    use leptos_reactive::create_effect;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    create_scope(|cx| {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let (a, set_a) = create_signal(cx, 0);
+
+        let b = create_memo(cx, {
+            let call_count = call_count.clone();
+            move |_| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                a() * 2
+            }
+        });
+
+        // an effect reading the memo pulls it up to date on every change
+        create_effect(cx, move |_| b());
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
+
+        set_a(1);
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+        set_a(2);
+        assert_eq!(call_count.load(Ordering::Relaxed), 3);
+
+        // reading it afterwards doesn't re-run the calculation
+        assert_eq!(b(), 4);
+        assert_eq!(call_count.load(Ordering::Relaxed), 3);
+    })
+    .dispose()
+}
+
+#[test]
+fn unread_memo_chain_is_lazy() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    create_scope(|cx| {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let (a, set_a) = create_signal(cx, 0);
+
+        let b = create_memo(cx, {
+            let call_count = call_count.clone();
+            move |_| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                a() + 1
+            }
+        });
+        let c = create_memo(cx, {
+            let call_count = call_count.clone();
+            move |_| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                b() + 1
+            }
+        });
+
+        // reading the end of the chain runs both
+        assert_eq!(c(), 2);
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+
+        // neither runs again until something reads the chain
+        set_a(5);
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+        assert_eq!(c(), 7);
+        assert_eq!(call_count.load(Ordering::Relaxed), 4);
     })
     .dispose()
 }
@@ -0,0 +1,135 @@
+#![cfg(feature = "multithreaded")]
+
+use leptos_reactive::{
+    create_isomorphic_effect, create_memo, create_rw_signal, create_scope, create_signal,
+    on_cleanup, Memo, ReadSignal, Resource, RwSignal, Scope, ScopeDisposer, WriteSignal,
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn reactive_types_are_send_and_sync() {
+    assert_send_sync::<Scope>();
+    assert_send_sync::<ScopeDisposer>();
+    assert_send_sync::<ReadSignal<String>>();
+    assert_send_sync::<WriteSignal<String>>();
+    assert_send_sync::<RwSignal<String>>();
+    assert_send_sync::<Memo<String>>();
+    assert_send_sync::<Resource<usize, String>>();
+}
+
+#[test]
+fn signal_can_be_set_from_another_thread() {
+    create_scope(|cx| {
+        let (count, set_count) = create_signal(cx, 0);
+        let double = create_memo(cx, move |_| count() * 2);
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        create_isomorphic_effect(cx, {
+            let log = Arc::clone(&log);
+            move |_| log.lock().unwrap().push(double())
+        });
+
+        std::thread::spawn(move || set_count(1)).join().unwrap();
+
+        assert_eq!(double(), 2);
+        assert_eq!(*log.lock().unwrap(), vec![0, 2]);
+    })
+    .dispose();
+}
+
+#[test]
+fn scope_can_be_disposed_on_another_thread() {
+    let cleaned_up = Arc::new(Mutex::new(false));
+    let disposer = create_scope({
+        let cleaned_up = Arc::clone(&cleaned_up);
+        move |cx| {
+            let name = create_rw_signal(cx, "Alice".to_string());
+            assert_eq!(name.get(), "Alice");
+            on_cleanup(cx, move || *cleaned_up.lock().unwrap() = true);
+        }
+    });
+
+    std::thread::spawn(move || disposer.dispose())
+        .join()
+        .unwrap();
+    assert!(*cleaned_up.lock().unwrap());
+}
+
+#[test]
+fn effect_and_memo_running_on_different_threads_track_their_own_dependencies() {
+    create_scope(|cx| {
+        let (y, set_y) = create_signal(cx, 0);
+        let (memo_started, started) = mpsc::channel();
+        let started = Mutex::new(started);
+        let computing_memo: Arc<Mutex<Option<JoinHandle<Memo<i32>>>>> = Default::default();
+        let effect_runs = Arc::new(AtomicUsize::new(0));
+
+        create_isomorphic_effect(cx, {
+            let computing_memo = Arc::clone(&computing_memo);
+            let effect_runs = Arc::clone(&effect_runs);
+            move |_| {
+                effect_runs.fetch_add(1, Ordering::SeqCst);
+                // while this effect runs, another thread starts computing a memo...
+                let memo_started = memo_started.clone();
+                *computing_memo.lock().unwrap() = Some(std::thread::spawn(move || {
+                    let memo = create_memo(cx, move |_| {
+                        _ = memo_started.send(());
+                        std::thread::sleep(Duration::from_millis(50));
+                        y()
+                    });
+                    _ = memo();
+                    memo
+                }));
+                // ...and reads `y` after the effect has finished, unless it waits its turn
+                _ = started
+                    .lock()
+                    .unwrap()
+                    .recv_timeout(Duration::from_millis(100));
+            }
+        });
+        let memo = computing_memo
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap()
+            .join()
+            .unwrap();
+
+        set_y(5);
+        assert_eq!(memo(), 5);
+        assert_eq!(effect_runs.load(Ordering::SeqCst), 1);
+    })
+    .dispose();
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn reentrant_update_panics_instead_of_deadlocking() {
+    create_scope(|cx| {
+        let (_, set_count) = create_signal(cx, 0);
+        set_count.update(|n| {
+            *n += 1;
+            set_count.update(|n| *n += 1);
+        });
+    })
+    .dispose();
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn setting_a_signal_while_reading_it_panics_instead_of_deadlocking() {
+    create_scope(|cx| {
+        let (count, set_count) = create_signal(cx, 0);
+        count.with(|_| set_count(1));
+    })
+    .dispose();
+}
@@ -0,0 +1,129 @@
+use leptos_reactive::{
+    create_query, create_scope, create_signal, provide_query_client, QueryOptions,
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+fn counting_fetcher(fetches: &Arc<AtomicUsize>) -> impl Fn(u32) -> std::future::Ready<String> {
+    let fetches = Arc::clone(fetches);
+    move |id| {
+        let fetches = fetches.fetch_add(1, Ordering::Relaxed) + 1;
+        std::future::ready(format!("user {id} ({fetches})"))
+    }
+}
+
+#[test]
+fn queries_with_the_same_key_share_a_fresh_value() {
+    create_scope(|cx| {
+        provide_query_client(
+            cx,
+            QueryOptions {
+                stale_time: Duration::from_secs(60),
+                ..Default::default()
+            },
+        );
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let a = create_query(cx, || 1, counting_fetcher(&fetches));
+        let b = create_query(cx, || 1, counting_fetcher(&fetches));
+        assert_eq!(fetches.load(Ordering::Relaxed), 1);
+        assert_eq!(a(), Some("user 1 (1)".to_string()));
+        assert_eq!(b(), Some("user 1 (1)".to_string()));
+
+        // a different key is cached separately
+        let c = create_query(cx, || 2, counting_fetcher(&fetches));
+        assert_eq!(c(), Some("user 2 (2)".to_string()));
+    })
+    .dispose();
+}
+
+#[test]
+fn stale_value_is_refetched_for_new_query() {
+    create_scope(|cx| {
+        provide_query_client(cx, QueryOptions::default());
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let a = create_query(cx, || 1, counting_fetcher(&fetches));
+        assert_eq!(a(), Some("user 1 (1)".to_string()));
+
+        // with no stale time, a new query refetches, and every query sees the new value
+        let b = create_query(cx, || 1, counting_fetcher(&fetches));
+        assert_eq!(fetches.load(Ordering::Relaxed), 2);
+        assert_eq!(a(), Some("user 1 (2)".to_string()));
+        assert_eq!(b(), Some("user 1 (2)".to_string()));
+    })
+    .dispose();
+}
+
+#[test]
+fn invalidate_refetches_key() {
+    create_scope(|cx| {
+        let client = provide_query_client(
+            cx,
+            QueryOptions {
+                stale_time: Duration::from_secs(60),
+                ..Default::default()
+            },
+        );
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let a = create_query(cx, || 1, counting_fetcher(&fetches));
+        let b = create_query(cx, || 2, counting_fetcher(&fetches));
+        client.invalidate::<u32, String>(&1);
+        assert_eq!(a(), Some("user 1 (3)".to_string()));
+        assert_eq!(b(), Some("user 2 (2)".to_string()));
+    })
+    .dispose();
+}
+
+#[test]
+fn unused_values_are_garbage_collected() {
+    create_scope(|cx| {
+        let (id, set_id) = create_signal(cx, 1);
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        // values are kept for the gc time after nothing uses them...
+        cx.child_scope({
+            let fetches = Arc::clone(&fetches);
+            move |cx| {
+                provide_query_client(
+                    cx,
+                    QueryOptions {
+                        stale_time: Duration::from_secs(60),
+                        gc_time: Duration::from_secs(60),
+                    },
+                );
+                let user = create_query(cx, id, counting_fetcher(&fetches));
+                set_id(2);
+                set_id(1);
+                assert_eq!(fetches.load(Ordering::Relaxed), 2);
+                assert_eq!(user(), Some("user 1 (1)".to_string()));
+            }
+        });
+
+        // ...and removed right away without one
+        cx.child_scope({
+            let fetches = Arc::clone(&fetches);
+            move |cx| {
+                provide_query_client(
+                    cx,
+                    QueryOptions {
+                        stale_time: Duration::from_secs(60),
+                        gc_time: Duration::ZERO,
+                    },
+                );
+                let user = create_query(cx, id, counting_fetcher(&fetches));
+                set_id(2);
+                set_id(1);
+                assert_eq!(fetches.load(Ordering::Relaxed), 5);
+                assert_eq!(user(), Some("user 1 (5)".to_string()));
+            }
+        });
+    })
+    .dispose();
+}
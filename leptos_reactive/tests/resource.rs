@@ -0,0 +1,173 @@
+#![cfg(all(feature = "ssr", not(feature = "multithreaded")))]
+
+use futures::channel::oneshot;
+use leptos_reactive::{
+    create_local_resource, create_resource_with_initial_value, create_resource_with_serializer,
+    create_scope, create_signal, provide_context, run_scope_undisposed, ResourceSerializer,
+    SerializationError, SuspenseContext,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+// resolves each load by hand, so they can finish out of order
+async fn run(f: impl std::future::Future<Output = ()>) {
+    tokio::task::LocalSet::new().run_until(f).await
+}
+
+async fn tick() {
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[test]
+fn stale_resource_load_is_discarded() {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(run(async {
+            let senders = Rc::new(RefCell::new(Vec::new()));
+            let (cx, disposer) = run_scope_undisposed(|cx| cx);
+
+            let (id, set_id) = create_signal(cx, 0);
+            let resource = create_resource_with_initial_value(
+                cx,
+                id,
+                {
+                    let senders = Rc::clone(&senders);
+                    move |id| {
+                        let (tx, rx) = oneshot::channel::<()>();
+                        senders.borrow_mut().push(tx);
+                        async move {
+                            _ = rx.await;
+                            id
+                        }
+                    }
+                },
+                None,
+            );
+
+            // start a second load while the first is still pending
+            set_id(1);
+            assert_eq!(senders.borrow().len(), 2);
+
+            // the newer load finishes first...
+            let second = senders.borrow_mut().pop().unwrap();
+            _ = second.send(());
+            tick().await;
+            assert_eq!(resource.read(), Some(1));
+            assert!(!resource.loading());
+
+            // ...and the older one doesn't overwrite it
+            let first = senders.borrow_mut().pop().unwrap();
+            assert!(first.send(()).is_err());
+            tick().await;
+            assert_eq!(resource.read(), Some(1));
+
+            disposer.dispose();
+        }));
+}
+
+#[test]
+fn pending_resource_load_is_aborted_on_dispose() {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(run(async {
+            let (tx, rx) = oneshot::channel::<()>();
+            let disposer = create_scope(|cx| {
+                let rx = RefCell::new(Some(rx));
+                create_resource_with_initial_value(
+                    cx,
+                    || (),
+                    move |_| {
+                        let rx = rx.borrow_mut().take().unwrap();
+                        async move {
+                            _ = rx.await;
+                        }
+                    },
+                    None,
+                );
+            });
+            tick().await;
+            assert!(!tx.is_canceled());
+
+            // dropping the load's Future drops the receiver
+            disposer.dispose();
+            tick().await;
+            assert!(tx.is_canceled());
+        }));
+}
+
+#[test]
+fn local_resource_is_never_loaded_or_serialized_on_the_server() {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(run(async {
+            let (cx, disposer) = run_scope_undisposed(|cx| cx);
+            let suspense = SuspenseContext::new(cx);
+            provide_context(cx, suspense);
+
+            let runs = Rc::new(Cell::new(0));
+            // an `Rc` can't be serialized
+            let local = create_local_resource(cx, || (), {
+                let runs = Rc::clone(&runs);
+                move |_| {
+                    runs.set(runs.get() + 1);
+                    async { Rc::new(()) }
+                }
+            });
+            create_resource_with_initial_value(cx, || (), |_| async { 1 }, Some(1));
+
+            assert_eq!(local.read(), None);
+            tick().await;
+            assert_eq!(runs.get(), 0);
+            assert!(!local.loading());
+            // reading it doesn't make `<Suspense/>` wait for it
+            assert!(suspense.ready());
+
+            // only the other resource is sent to the client
+            assert_eq!(cx.all_resources().len(), 1);
+            assert_eq!(cx.serialization_resolvers().len(), 1);
+
+            disposer.dispose();
+        }));
+}
+
+// a format that only works for strings, to check that it's used instead of JSON
+#[derive(Copy, Clone)]
+struct Reversed;
+
+impl ResourceSerializer<String> for Reversed {
+    fn serialize(&self, value: &String) -> Result<String, SerializationError> {
+        Ok(value.chars().rev().collect())
+    }
+
+    fn deserialize(&self, data: &str) -> Result<String, SerializationError> {
+        Ok(data.chars().rev().collect())
+    }
+}
+
+#[test]
+fn resource_is_serialized_with_its_serializer() {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(run(async {
+            let (cx, disposer) = run_scope_undisposed(|cx| cx);
+            let resource = create_resource_with_serializer(
+                cx,
+                || (),
+                |_| async { "hello".to_string() },
+                Reversed,
+            );
+
+            let (_, serialized) = resource.to_serialization_resolver().await;
+            assert_eq!(serialized, "olleh");
+
+            disposer.dispose();
+        }));
+}
@@ -0,0 +1,41 @@
+use leptos_reactive::{create_scope, create_signal, on_cleanup, run_scope, run_scope_undisposed};
+
+#[test]
+fn disposing_root_scope_frees_runtime() {
+    let (count, disposer) = run_scope_undisposed(|cx| create_signal(cx, 0).0);
+    assert_eq!(count(), 0);
+
+    disposer.dispose();
+    let read_after_dispose = std::panic::catch_unwind(move || count.get());
+    assert!(read_after_dispose.is_err());
+}
+
+#[test]
+fn run_scope_frees_runtime() {
+    let count = run_scope(|cx| create_signal(cx, 0).0);
+    let read_after_dispose = std::panic::catch_unwind(move || count.get());
+    assert!(read_after_dispose.is_err());
+}
+
+#[test]
+fn disposing_root_scope_disposes_children() {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    let cleaned_up = Arc::new(AtomicBool::new(false));
+
+    let disposer = create_scope({
+        let cleaned_up = cleaned_up.clone();
+        move |cx| {
+            cx.child_scope(move |cx| {
+                on_cleanup(cx, move || cleaned_up.store(true, Ordering::Relaxed));
+            });
+        }
+    });
+
+    assert!(!cleaned_up.load(Ordering::Relaxed));
+    disposer.dispose();
+    assert!(cleaned_up.load(Ordering::Relaxed));
+}
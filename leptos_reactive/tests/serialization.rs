@@ -0,0 +1,36 @@
+#![cfg(feature = "resource")]
+
+use leptos_reactive::{Json, ResourceSerializer, SerializationError};
+
+#[test]
+fn serializers_round_trip_values() {
+    let values = vec![1.5_f64, -2.0, 1024.25];
+
+    let json = Json.serialize(&values).unwrap();
+    assert_eq!(json, "[1.5,-2.0,1024.25]");
+    assert_eq!(Json.deserialize(&json), Ok(values.clone()));
+
+    #[cfg(feature = "bincode")]
+    {
+        let bincode = leptos_reactive::Bincode.serialize(&values).unwrap();
+        assert_eq!(
+            leptos_reactive::Bincode.deserialize(&bincode),
+            Ok(values.clone())
+        );
+    }
+
+    #[cfg(feature = "miniserde")]
+    {
+        let miniserde = leptos_reactive::Miniserde.serialize(&values).unwrap();
+        assert_eq!(
+            leptos_reactive::Miniserde.deserialize(&miniserde),
+            Ok(values)
+        );
+    }
+}
+
+#[test]
+fn invalid_data_is_an_error() {
+    let res: Result<Vec<f64>, _> = Json.deserialize("[1.5,");
+    assert!(matches!(res, Err(SerializationError::Deserialize(_))));
+}
@@ -1,4 +1,11 @@
-use leptos_reactive::{create_scope, create_signal};
+use leptos_reactive::{
+    create_isomorphic_effect, create_memo, create_resource, create_rw_signal, create_scope,
+    create_signal, create_signal_with_eq, run_scope_undisposed, SignalError,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 #[test]
 fn basic_signal() {
@@ -25,3 +32,117 @@ fn derived_signals() {
     })
     .dispose()
 }
+
+#[test]
+fn try_methods_return_errors_once_disposed() {
+    // on the server, resources load on a tokio runtime
+    #[cfg(feature = "ssr")]
+    let (runtime, local) = (
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap(),
+        tokio::task::LocalSet::new(),
+    );
+    #[cfg(feature = "ssr")]
+    let _entered = (runtime.enter(), local.enter());
+
+    let (cx, disposer) = run_scope_undisposed(|cx| cx);
+
+    let mut handles = None;
+    let child = cx.child_scope(|cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let b = create_rw_signal(cx, 1);
+        let c = create_memo(cx, move |_| a() + 1);
+        let d = create_resource(cx, || (), |_| async { 2 });
+        handles = Some((a, set_a, b, c, d));
+    });
+    let (a, set_a, b, c, d) = handles.unwrap();
+
+    assert_eq!(set_a.try_set(1), Ok(()));
+    assert_eq!(a.try_get(), Ok(1));
+    assert_eq!(b.try_update(|n| *n += 1), Ok(()));
+    assert_eq!(b.try_with(|n| *n), Ok(2));
+    assert_eq!(c.try_get(), Ok(2));
+    assert_eq!(d.try_read(), Ok(Some(2)));
+    assert_eq!(d.try_get(), Ok(Some(2)));
+    assert_eq!(d.try_with(|n| n.map(|n| n * 10)), Ok(Some(20)));
+
+    // the runtime is still alive, but the signals have been disposed
+    child.dispose();
+    assert!(matches!(a.try_get(), Err(SignalError::Disposed(_))));
+    assert!(matches!(set_a.try_set(2), Err(SignalError::Disposed(_))));
+    assert!(matches!(b.try_set(2), Err(SignalError::Disposed(_))));
+    assert!(matches!(c.try_get(), Err(SignalError::Disposed(_))));
+    assert!(matches!(
+        d.try_read(),
+        Err(SignalError::ResourceDisposed(_))
+    ));
+    assert!(matches!(
+        d.try_with(|_| ()),
+        Err(SignalError::ResourceDisposed(_))
+    ));
+
+    disposer.dispose();
+    assert_eq!(a.try_get(), Err(SignalError::RuntimeDisposed));
+    assert_eq!(
+        set_a.try_update(|n| *n += 1),
+        Err(SignalError::RuntimeDisposed)
+    );
+}
+
+#[test]
+fn signal_with_eq_skips_equal_updates() {
+    create_scope(|cx| {
+        // only the tens digit matters
+        let (a, set_a) = create_signal_with_eq(cx, 1, |a: &i32, b: &i32| a / 10 == b / 10);
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        create_isomorphic_effect(cx, {
+            let runs = Arc::clone(&runs);
+            move |_| {
+                a();
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+
+        set_a(5);
+        set_a.try_set(6).unwrap();
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        // the value is still updated, even though subscribers weren't notified
+        assert_eq!(a(), 6);
+
+        set_a(16);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+
+        // updating in place leaves nothing to compare with, so it always notifies
+        set_a.update(|n| *n += 1);
+        assert_eq!(runs.load(Ordering::Relaxed), 3);
+    })
+    .dispose()
+}
+
+#[test]
+fn set_if_changed_skips_equal_values() {
+    create_scope(|cx| {
+        let count = create_rw_signal(cx, 0);
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        create_isomorphic_effect(cx, {
+            let runs = Arc::clone(&runs);
+            move |_| {
+                count();
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        count.set_if_changed(0);
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        count.set_if_changed(1);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        // `set` always notifies
+        count.set(1);
+        assert_eq!(runs.load(Ordering::Relaxed), 3);
+    })
+    .dispose()
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "ssr")]
+
+use futures::{channel::mpsc, StreamExt};
+use leptos_reactive::{create_signal, run_scope_undisposed};
+
+fn run(f: impl std::future::Future<Output = ()>) {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(tokio::task::LocalSet::new().run_until(f))
+}
+
+async fn tick() {
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[test]
+fn spawned_task_is_aborted_when_scope_is_disposed() {
+    run(async {
+        let (tx, mut rx) = mpsc::unbounded::<i32>();
+        let ((count, task), disposer) = run_scope_undisposed(move |cx| {
+            let (count, set_count) = create_signal(cx, 0);
+            let task = cx.spawn(async move {
+                while let Some(n) = rx.next().await {
+                    set_count(n);
+                }
+            });
+            (count, task)
+        });
+
+        tx.unbounded_send(1).unwrap();
+        tick().await;
+        assert_eq!(count(), 1);
+        assert!(!task.is_finished());
+
+        // the task stops, and drops the receiver, along with the scope
+        disposer.dispose();
+        assert!(task.is_finished());
+        tick().await;
+        assert!(tx.is_closed());
+    });
+}
+
+#[test]
+fn spawned_task_can_be_aborted_or_finish_early() {
+    run(async {
+        let (tx, mut rx) = mpsc::unbounded::<()>();
+        let ((looping, finishing), disposer) = run_scope_undisposed(move |cx| {
+            let looping = cx.spawn(async move { while rx.next().await.is_some() {} });
+            let finishing = cx.spawn(async {});
+            (looping, finishing)
+        });
+
+        tick().await;
+        assert!(finishing.is_finished());
+        assert!(!looping.is_finished());
+
+        looping.abort();
+        assert!(looping.is_finished());
+        tick().await;
+        assert!(tx.is_closed());
+
+        disposer.dispose();
+    });
+}
+
+#[test]
+fn task_spawned_after_others_finished_is_still_aborted() {
+    run(async {
+        let (tx, mut rx) = mpsc::unbounded::<()>();
+        let (cx, disposer) = run_scope_undisposed(|cx| cx);
+
+        let finished = (0..10).map(|_| cx.spawn(async {})).collect::<Vec<_>>();
+        tick().await;
+        assert!(finished.iter().all(|task| task.is_finished()));
+
+        // spawning again drops the finished tasks, but not the ones still running
+        let looping = cx.spawn(async move { while rx.next().await.is_some() {} });
+        let finishing = cx.spawn(async {});
+        tick().await;
+        assert!(finishing.is_finished());
+        assert!(!looping.is_finished());
+
+        disposer.dispose();
+        assert!(looping.is_finished());
+        tick().await;
+        assert!(tx.is_closed());
+    });
+}
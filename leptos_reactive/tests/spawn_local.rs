@@ -0,0 +1,59 @@
+#![cfg(not(any(feature = "csr", feature = "hydrate", feature = "ssr")))]
+
+use leptos_reactive::{create_scope, spawn_local};
+use std::{
+    cell::RefCell,
+    panic::{catch_unwind, AssertUnwindSafe},
+    rc::Rc,
+};
+
+#[test]
+fn task_spawned_by_a_running_task_runs_after_it() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    spawn_local({
+        let log = Rc::clone(&log);
+        async move {
+            log.borrow_mut().push("outer started");
+            spawn_local({
+                let log = Rc::clone(&log);
+                async move { log.borrow_mut().push("inner") }
+            });
+            log.borrow_mut().push("outer finished");
+        }
+    });
+
+    // both have run by the time the outermost spawn returns
+    assert_eq!(
+        *log.borrow(),
+        vec!["outer started", "outer finished", "inner"]
+    );
+}
+
+#[test]
+fn panicking_task_does_not_leave_tasks_queued() {
+    let panicked = catch_unwind(AssertUnwindSafe(|| {
+        spawn_local(async {
+            spawn_local(async {});
+            panic!("task failed");
+        })
+    }));
+    assert!(panicked.is_err());
+
+    // later tasks run right away rather than waiting for a task that is no longer running
+    let ran = Rc::new(RefCell::new(false));
+    spawn_local({
+        let ran = Rc::clone(&ran);
+        async move { *ran.borrow_mut() = true }
+    });
+    assert!(*ran.borrow());
+}
+
+#[test]
+#[should_panic(expected = "Scope::spawn needs an executor")]
+fn scope_spawn_without_executor_panics_instead_of_blocking() {
+    // the task never finishes, so blocking on it would hang
+    create_scope(|cx| {
+        cx.spawn(futures::future::pending());
+    })
+    .dispose();
+}
@@ -0,0 +1,199 @@
+use leptos_macro::Store;
+use leptos_reactive::{create_effect, create_scope, create_store, Scope, StoreField, ThreadSafe};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+// the derive refers to the store types through the `leptos` crate
+use leptos_reactive as leptos;
+
+#[derive(Store, Clone, Debug, PartialEq)]
+struct App {
+    user: User,
+    #[store(key: usize = |todo| todo.id)]
+    todos: Vec<Todo>,
+}
+
+#[derive(Store, Clone, Debug, PartialEq)]
+struct User {
+    name: String,
+    email: String,
+}
+
+#[derive(Store, Clone, Debug, PartialEq)]
+struct Todo {
+    id: usize,
+    title: String,
+}
+
+fn app() -> App {
+    App {
+        user: User {
+            name: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+        },
+        todos: vec![
+            Todo {
+                id: 0,
+                title: "Buy milk".to_string(),
+            },
+            Todo {
+                id: 1,
+                title: "Walk dog".to_string(),
+            },
+        ],
+    }
+}
+
+fn count_runs(cx: Scope, read: impl Fn() + ThreadSafe + 'static) -> Arc<AtomicUsize> {
+    let runs = Arc::new(AtomicUsize::new(0));
+    create_effect(cx, {
+        let runs = Arc::clone(&runs);
+        move |_| {
+            read();
+            runs.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    runs
+}
+
+#[test]
+fn field_changes_only_notify_their_readers() {
+    create_scope(|cx| {
+        let store = create_store(cx, app());
+
+        let name_runs = count_runs(cx, {
+            let store = store.clone();
+            move || _ = store.user().name().get()
+        });
+        let email_runs = count_runs(cx, {
+            let store = store.clone();
+            move || _ = store.user().email().get()
+        });
+        let user_runs = count_runs(cx, {
+            let store = store.clone();
+            move || _ = store.user().get()
+        });
+
+        store.user().name().set("Bob".to_string());
+        assert_eq!(store.user().name().get(), "Bob");
+        assert_eq!(name_runs.load(Ordering::Relaxed), 2);
+        assert_eq!(email_runs.load(Ordering::Relaxed), 1);
+        // readers of a containing field see changes inside it
+        assert_eq!(user_runs.load(Ordering::Relaxed), 2);
+
+        // replacing a containing field notifies the readers of the fields inside it
+        store
+            .user()
+            .update(|user| user.email = "bob@example.com".to_string());
+        assert_eq!(name_runs.load(Ordering::Relaxed), 3);
+        assert_eq!(email_runs.load(Ordering::Relaxed), 2);
+    })
+    .dispose();
+}
+
+#[test]
+fn keyed_items_are_tracked_separately() {
+    create_scope(|cx| {
+        let store = create_store(cx, app());
+        assert_eq!(store.todos().keys(), vec![0, 1]);
+
+        let first_runs = count_runs(cx, {
+            let store = store.clone();
+            move || _ = store.todos().at_key(0).title().get()
+        });
+        let keys_runs = count_runs(cx, {
+            let store = store.clone();
+            move || _ = store.todos().keys()
+        });
+
+        // changing one item doesn't notify readers of another, or of the list of keys
+        store.todos().at_key(1).title().set("Walk cat".to_string());
+        assert_eq!(first_runs.load(Ordering::Relaxed), 1);
+        assert_eq!(keys_runs.load(Ordering::Relaxed), 1);
+
+        // adding an item does
+        store.todos().update(|todos| {
+            todos.insert(
+                0,
+                Todo {
+                    id: 2,
+                    title: "Feed fish".to_string(),
+                },
+            )
+        });
+        assert_eq!(keys_runs.load(Ordering::Relaxed), 2);
+        assert_eq!(store.todos().keys(), vec![2, 0, 1]);
+
+        // items are found by key, not by position
+        let titles = store
+            .todos()
+            .items()
+            .into_iter()
+            .map(|todo| todo.title().get())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Feed fish", "Buy milk", "Walk cat"]);
+    })
+    .dispose();
+}
+
+#[test]
+fn keys_with_the_same_hash_are_tracked_separately() {
+    use std::hash::{Hash, Hasher};
+
+    // every id hashes the same
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Id(usize);
+
+    impl Hash for Id {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            0.hash(state);
+        }
+    }
+
+    #[derive(Store, Clone, Debug, PartialEq)]
+    struct List {
+        #[store(key: Id = |item| Id(item.0))]
+        items: Vec<(usize, String)>,
+    }
+
+    create_scope(|cx| {
+        let store = create_store(
+            cx,
+            List {
+                items: vec![(0, "a".to_string()), (1, "b".to_string())],
+            },
+        );
+        let first_runs = count_runs(cx, {
+            let store = store.clone();
+            move || _ = store.items().at_key(Id(0)).get()
+        });
+
+        store.items().at_key(Id(1)).update(|item| item.1.push('!'));
+        assert_eq!(first_runs.load(Ordering::Relaxed), 1);
+        assert_eq!(store.items().at_key(Id(1)).get().1, "b!");
+    })
+    .dispose();
+}
+
+#[cfg(feature = "debug")]
+#[test]
+fn removing_keyed_items_disposes_their_triggers() {
+    create_scope(|cx| {
+        let store = create_store(cx, app());
+        let before = cx.allocations().counts();
+
+        // reading an item creates signals to track it, and the fields inside it
+        _ = store.todos().at_key(1).get();
+        _ = store.todos().at_key(1).title().get();
+        assert!(cx.allocations().counts().signals > before.signals);
+
+        store
+            .todos()
+            .update(|todos| todos.retain(|todo| todo.id != 1));
+        cx.assert_allocations(before);
+        assert_eq!(store.todos().keys(), vec![0]);
+    })
+    .dispose();
+}
@@ -0,0 +1,61 @@
+use futures::StreamExt;
+use leptos_reactive::{create_memo, create_rw_signal, create_signal, run_scope_undisposed};
+
+#[test]
+fn signal_to_stream_yields_each_value_until_disposed() {
+    let ((counts, doubles, names), disposer) = run_scope_undisposed(|cx| {
+        let (count, set_count) = create_signal(cx, 0);
+        let double = create_memo(cx, move |_| count() * 2);
+        let name = create_rw_signal(cx, "Alice".to_string());
+
+        let counts = count.to_stream(cx);
+        let doubles = double.to_stream(cx);
+        let names = name.to_stream(cx);
+
+        set_count(1);
+        set_count(2);
+        name.set("Bob".to_string());
+        (counts, doubles, names)
+    });
+
+    // disposing of the scope ends the streams
+    disposer.dispose();
+    futures::executor::block_on(async move {
+        assert_eq!(counts.collect::<Vec<_>>().await, vec![0, 1, 2]);
+        assert_eq!(doubles.collect::<Vec<_>>().await, vec![0, 2, 4]);
+        assert_eq!(names.collect::<Vec<_>>().await, vec!["Alice", "Bob"]);
+    });
+}
+
+#[cfg(feature = "ssr")]
+#[test]
+fn signal_from_stream_follows_stream_until_disposed() {
+    use futures::channel::mpsc;
+    use leptos_reactive::{create_signal_from_stream, run_scope_undisposed};
+
+    async fn tick() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(tokio::task::LocalSet::new().run_until(async {
+            let (tx, rx) = mpsc::unbounded();
+            let (latest, disposer) =
+                run_scope_undisposed(move |cx| create_signal_from_stream(cx, rx, 0));
+            assert_eq!(latest(), 0);
+
+            tx.unbounded_send(1).unwrap();
+            tx.unbounded_send(2).unwrap();
+            tick().await;
+            assert_eq!(latest(), 2);
+
+            // the stream is dropped along with the scope
+            disposer.dispose();
+            tick().await;
+            assert!(tx.is_closed());
+        }));
+}
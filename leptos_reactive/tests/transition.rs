@@ -0,0 +1,216 @@
+#![cfg(feature = "transition")]
+
+use futures::channel::oneshot;
+use leptos_reactive::{
+    batch, create_isomorphic_effect, create_resource_with_initial_value, create_signal,
+    provide_context, run_scope_undisposed, use_transition, ReadSignal, Scope, SuspenseContext,
+    TestExecutor,
+};
+use std::sync::{Arc, Mutex};
+
+type Senders = Arc<Mutex<Vec<oneshot::Sender<()>>>>;
+
+/// Creates a resource that loads whenever `source` changes, read under its own
+/// `<Suspense/>`. Each load waits until its sender in `senders` is used.
+fn suspended_resource(cx: Scope, source: ReadSignal<i32>, senders: &Senders) -> SuspenseContext {
+    let mut suspense = None;
+    cx.child_scope(|cx| {
+        let context = SuspenseContext::new(cx);
+        provide_context(cx, context);
+
+        let resource = create_resource_with_initial_value(
+            cx,
+            source,
+            {
+                let senders = Arc::clone(senders);
+                move |id| {
+                    let (tx, rx) = oneshot::channel::<()>();
+                    senders.lock().unwrap().push(tx);
+                    async move {
+                        _ = rx.await;
+                        id
+                    }
+                }
+            },
+            Some(0),
+        );
+        // reading it is what makes the suspense wait for it
+        resource.read();
+        suspense = Some(context);
+    });
+    suspense.unwrap()
+}
+
+fn resolve_all(senders: &Senders, executor: &TestExecutor) {
+    for tx in senders.lock().unwrap().drain(..) {
+        _ = tx.send(());
+    }
+    executor.run_until_stalled();
+}
+
+#[test]
+fn transition_only_keeps_its_own_suspense_showing_children() {
+    let executor = TestExecutor::new();
+    let senders = Senders::default();
+    let ((transition, set_user_id, set_page, user_suspense, posts_suspense), disposer) =
+        run_scope_undisposed({
+            let executor = executor.clone();
+            let senders = Arc::clone(&senders);
+            move |cx| {
+                cx.set_executor(executor);
+                let transition = use_transition(cx);
+                let (user_id, set_user_id) = create_signal(cx, 0);
+                let (page, set_page) = create_signal(cx, 0);
+                let user_suspense = suspended_resource(cx, user_id, &senders);
+                let posts_suspense = suspended_resource(cx, page, &senders);
+                (
+                    transition,
+                    set_user_id,
+                    set_page,
+                    user_suspense,
+                    posts_suspense,
+                )
+            }
+        });
+    resolve_all(&senders, &executor);
+
+    transition.start(move || set_user_id(1));
+    executor.flush_microtasks();
+    assert!(transition.is_pending());
+    assert!(user_suspense.transition_pending());
+    assert!(!posts_suspense.transition_pending());
+
+    // a resource that loads outside the transition still shows its fallback
+    set_page(1);
+    executor.flush_microtasks();
+    assert!(!posts_suspense.ready());
+    assert!(!posts_suspense.transition_pending());
+    assert!(user_suspense.transition_pending());
+
+    resolve_all(&senders, &executor);
+    assert!(!transition.is_pending());
+    assert!(user_suspense.ready());
+    assert!(!user_suspense.transition_pending());
+    assert!(posts_suspense.ready());
+
+    disposer.dispose();
+}
+
+#[test]
+fn transition_started_in_effect_tracks_resources_loaded_by_later_effects() {
+    let executor = TestExecutor::new();
+    let senders = Senders::default();
+    let ((transition, set_route, suspense), disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        let senders = Arc::clone(&senders);
+        move |cx| {
+            cx.set_executor(executor);
+            let transition = use_transition(cx);
+            let (route, set_route) = create_signal(cx, 0);
+            let (id, set_id) = create_signal(cx, 0);
+
+            // like a router, which responds to a URL change by starting a transition
+            create_isomorphic_effect(cx, move |_| {
+                let route = route();
+                transition.start(move || set_id(route));
+            });
+
+            let suspense = suspended_resource(cx, id, &senders);
+            (transition, set_route, suspense)
+        }
+    });
+    resolve_all(&senders, &executor);
+
+    set_route(1);
+    executor.flush_microtasks();
+    assert!(transition.is_pending());
+    assert!(suspense.transition_pending());
+
+    resolve_all(&senders, &executor);
+    assert!(!transition.is_pending());
+    assert!(suspense.ready());
+
+    disposer.dispose();
+}
+
+#[test]
+fn transition_ends_with_the_batch_it_was_started_in() {
+    let executor = TestExecutor::new();
+    let senders = Senders::default();
+    let ((cx, transition, set_id, suspense), disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        let senders = Arc::clone(&senders);
+        move |cx| {
+            cx.set_executor(executor);
+            let transition = use_transition(cx);
+            let (id, set_id) = create_signal(cx, 0);
+            let suspense = suspended_resource(cx, id, &senders);
+            (cx, transition, set_id, suspense)
+        }
+    });
+    resolve_all(&senders, &executor);
+
+    batch(cx, move || transition.start(move || set_id(1)));
+    resolve_all(&senders, &executor);
+    assert!(!transition.is_pending());
+
+    // changes made after the batch aren't part of the transition
+    set_id(2);
+    executor.flush_microtasks();
+    assert!(!transition.is_pending());
+    assert!(!suspense.ready());
+    assert!(!suspense.transition_pending());
+
+    resolve_all(&senders, &executor);
+    disposer.dispose();
+}
+
+#[test]
+fn disposing_a_loading_resource_ends_the_transition() {
+    let executor = TestExecutor::new();
+    let senders = Senders::default();
+    let ((transition, set_id, suspense, resource_scope), disposer) = run_scope_undisposed({
+        let executor = executor.clone();
+        let senders = Arc::clone(&senders);
+        move |cx| {
+            cx.set_executor(executor);
+            let transition = use_transition(cx);
+            let (id, set_id) = create_signal(cx, 0);
+
+            // the resource is created in a scope of its own, under the suspense
+            let suspense = SuspenseContext::new(cx);
+            provide_context(cx, suspense);
+            let resource_scope = cx.child_scope(|cx| {
+                let resource = create_resource_with_initial_value(
+                    cx,
+                    id,
+                    move |id| {
+                        let (tx, rx) = oneshot::channel::<()>();
+                        senders.lock().unwrap().push(tx);
+                        async move {
+                            _ = rx.await;
+                            id
+                        }
+                    },
+                    Some(0),
+                );
+                resource.read();
+            });
+            (transition, set_id, suspense, resource_scope)
+        }
+    });
+    resolve_all(&senders, &executor);
+
+    transition.start(move || set_id(1));
+    executor.flush_microtasks();
+    assert!(transition.is_pending());
+    assert!(!suspense.ready());
+
+    // the load will never finish, so nothing should keep waiting for it
+    resource_scope.dispose();
+    executor.run_until_stalled();
+    assert!(!transition.is_pending());
+    assert!(suspense.ready());
+
+    disposer.dispose();
+}
@@ -8,9 +8,6 @@ use typed_builder::TypedBuilder;
 #[cfg(not(feature = "ssr"))]
 use wasm_bindgen::JsCast;
 
-#[cfg(feature = "transition")]
-use leptos_reactive::use_transition;
-
 use crate::{
     create_location,
     matching::{get_route_matches, resolve_path, Branch, RouteMatch},
@@ -51,6 +48,8 @@ pub(crate) struct RouterContextInner {
     referrers: Rc<RefCell<Vec<LocationChange>>>,
     state: ReadSignal<State>,
     set_state: WriteSignal<State>,
+    #[cfg(feature = "transition")]
+    transition: Transition,
 }
 
 impl std::fmt::Debug for RouterContextInner {
@@ -122,8 +121,14 @@ impl RouterContext {
             let LocationChange { value, state, .. } = source();
             cx.untrack(move || {
                 if value != reference() {
-                    set_reference.update(move |r| *r = value);
-                    set_state.update(move |s| *s = state);
+                    let update = move || {
+                        set_reference.update(move |r| *r = value);
+                        set_state.update(move |s| *s = state);
+                    };
+                    #[cfg(feature = "transition")]
+                    transition.start(update);
+                    #[cfg(not(feature = "transition"))]
+                    update();
                 }
             });
         });
@@ -139,15 +144,19 @@ impl RouterContext {
             referrers,
             state,
             set_state,
+            #[cfg(feature = "transition")]
+            transition,
         });
 
         // handle all click events on anchor tags
         #[cfg(any(feature = "csr", feature = "hydrate"))]
-        leptos_dom::window_event_listener("click", {
-            let inner = Rc::clone(&inner);
-            move |ev| inner.clone().handle_anchor_click(ev)
-        });
-        // TODO on_cleanup remove event listener
+        {
+            let handle = leptos_dom::window_event_listener("click", {
+                let inner = Rc::clone(&inner);
+                move |ev| inner.clone().handle_anchor_click(ev)
+            });
+            on_cleanup(cx, move || handle.remove());
+        }
 
         Self { inner }
     }
@@ -167,11 +176,9 @@ impl RouterContextInner {
         to: &str,
         options: &NavigateOptions,
     ) -> Result<(), NavigationError> {
-        let cx = self.cx;
         let this = Rc::clone(&self);
 
-        // TODO untrack causes an error here
-        cx.untrack(move || {
+        untrack(move || {
             let resolved_to = if options.resolve {
                 this.base.resolve_path(to)
             } else {
@@ -205,32 +212,34 @@ impl RouterContextInner {
                             }
                             let len = self.referrers.borrow().len();
 
-                            #[cfg(feature = "transition")]
-                            let transition = use_transition(self.cx);
-                            //transition.start({
                             let set_reference = self.set_reference;
                             let set_state = self.set_state;
                             let referrers = self.referrers.clone();
                             let this = Rc::clone(&self);
-                            //move || {
-                            set_reference.update({
-                                let resolved = resolved_to.to_string();
-                                move |r| *r = resolved
-                            });
-                            set_state.update({
-                                let next_state = options.state.clone();
-                                move |state| *state = next_state
-                            });
-                            if referrers.borrow().len() == len {
-                                this.navigate_end(LocationChange {
-                                    value: resolved_to.to_string(),
-                                    replace: false,
-                                    scroll: true,
-                                    state: options.state.clone(),
-                                })
-                                //}
-                            }
-                            //});
+                            let navigate = move || {
+                                set_reference.update({
+                                    let resolved = resolved_to.to_string();
+                                    move |r| *r = resolved
+                                });
+                                set_state.update({
+                                    let next_state = options.state.clone();
+                                    move |state| *state = next_state
+                                });
+                                if referrers.borrow().len() == len {
+                                    this.navigate_end(LocationChange {
+                                        value: resolved_to.to_string(),
+                                        replace: false,
+                                        scroll: true,
+                                        state: options.state.clone(),
+                                    })
+                                }
+                            };
+
+                            // keep showing the current route until the new route's loaders resolve
+                            #[cfg(feature = "transition")]
+                            self.transition.start(navigate);
+                            #[cfg(not(feature = "transition"))]
+                            navigate();
                         }
                     }
 
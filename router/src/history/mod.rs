@@ -48,7 +48,7 @@ impl History for BrowserIntegration {
 
         let (location, set_location) = create_signal(cx, Self::current());
 
-        leptos_dom::window_event_listener("popstate", move |_| {
+        let handle = leptos_dom::window_event_listener("popstate", move |_| {
             log::debug!(
                 "[BrowserIntegration::location] popstate fired {:#?}",
                 Self::current()
@@ -73,6 +73,7 @@ impl History for BrowserIntegration {
                 log::debug!("RouterContext not found");
             }
         });
+        on_cleanup(cx, move || handle.remove());
 
         location
     }